@@ -0,0 +1,313 @@
+use std::fmt;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{Game, TurnPhase};
+use crate::attack::{AttackMode, Roll};
+use crate::cards::{is_set, Card};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Action {
+    PlaceTroops { territory: usize, troops: u32 },
+    // `dice` is the number of troops committed to the battle, rolled up to three at a time
+    Attack { from: usize, to: usize, dice: u32 },
+    // Attacks with everything available, rolling until the mode says to stop
+    AttackUntil { from: usize, to: usize, mode: AttackMode },
+    // Moves extra troops into a freshly captured territory
+    Advance { troops: u32 },
+    Fortify { from: usize, to: usize, troops: u32 },
+    EndPhase,
+    EndTurn,
+    TradeCards { cards: Vec<usize> },
+    // How many dice the player rolls from now on, when attacking and when defending. Chosen on the
+    // player's own turn, the defense holds until they choose again
+    ChooseDice { attack: u32, defense: u32 },
+    // Setup moves, taking an unowned territory and placing starting troops
+    Claim { territory: usize },
+    Deploy { territory: usize, troops: u32 },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Placed { territory: usize, troops: u32 },
+    Battle { attacker_losses: u32, defender_losses: u32, captured: bool, rolls: Vec<Roll> },
+    Advanced { troops: u32 },
+    Fortified { troops: u32 },
+    PhaseChanged(TurnPhase),
+    TurnEnded { next_player: usize },
+    CardsTraded { troops: u32 },
+    DiceChosen { attack: u32, defense: u32 },
+    Claimed { territory: usize },
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameError {
+    GameOver = 0,
+    WrongPhase = 1,
+    MustTradeCards = 2,
+    AdvancePending = 3,
+    NoAdvancePending = 4,
+    UnknownTerritory = 5,
+    NotOwned = 6,
+    OwnTerritory = 7,
+    NotAdjacent = 8,
+    NotEnoughTroops = 9,
+    NoTroops = 10,
+    NoSelection = 11,
+    InvalidCards = 12,
+    NotASet = 13,
+    UnplacedTroops = 14,
+    IllegalPhaseChange = 15,
+    NothingToUndo = 16,
+    NothingToRedo = 17,
+    InvalidDice = 18,
+    AlreadyMoved = 19,
+    AlreadyOwned = 20,
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            GameError::GameOver => "the game is already over",
+            GameError::WrongPhase => "that move is not allowed in the current phase",
+            GameError::MustTradeCards => "a set of cards has to be traded in first",
+            GameError::AdvancePending => "troops have to be advanced into the captured territory first",
+            GameError::NoAdvancePending => "there is no captured territory to advance into",
+            GameError::UnknownTerritory => "no such territory",
+            GameError::NotOwned => "the territory belongs to another player",
+            GameError::OwnTerritory => "you cannot attack your own territory",
+            GameError::NotAdjacent => "the territories are not connected",
+            GameError::NotEnoughTroops => "not enough troops",
+            GameError::NoTroops => "at least one troop has to be moved",
+            GameError::NoSelection => "select a territory first",
+            GameError::InvalidCards => "those cards are not in your hand",
+            GameError::NotASet => "those cards do not form a set",
+            GameError::UnplacedTroops => "all reinforcements have to be placed first",
+            GameError::IllegalPhaseChange => "the turn cannot go back to that phase",
+            GameError::NothingToUndo => "nothing to undo since the last dice roll or card draw",
+            GameError::NothingToRedo => "nothing to redo",
+            GameError::InvalidDice => "the rules don't allow rolling that many dice",
+            GameError::AlreadyMoved => "troops that were moved this turn can't move again",
+            GameError::AlreadyOwned => "that territory has already been claimed",
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl std::error::Error for GameError {}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn describe_error(error: GameError) -> String {
+    error.to_string()
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Game {
+    pub fn place_troops(&mut self, territory: usize, troops: u32) -> Result<(), GameError> {
+        self.apply(Action::PlaceTroops { territory, troops }).map(|_| ())
+    }
+    // Returns whether the target was captured
+    pub fn attack(&mut self, from: usize, to: usize, dice: u32) -> Result<bool, GameError> {
+        match self.apply(Action::Attack { from, to, dice })? {
+            Outcome::Battle { captured, .. } => Ok(captured),
+            _ => Ok(false),
+        }
+    }
+    pub fn advance(&mut self, troops: u32) -> Result<(), GameError> {
+        self.apply(Action::Advance { troops }).map(|_| ())
+    }
+    pub fn fortify(&mut self, from: usize, to: usize, troops: u32) -> Result<(), GameError> {
+        self.apply(Action::Fortify { from, to, troops }).map(|_| ())
+    }
+    pub fn end_phase(&mut self) -> Result<TurnPhase, GameError> {
+        self.apply(Action::EndPhase).map(|_| self.turn_phase())
+    }
+    pub fn end_turn(&mut self) -> Result<(), GameError> {
+        self.apply(Action::EndTurn).map(|_| ())
+    }
+    // Trades the cards at the given hand positions, returning the troops gained
+    pub fn trade_cards(&mut self, hand_indices: Vec<usize>) -> Result<u32, GameError> {
+        match self.apply(Action::TradeCards { cards: hand_indices })? {
+            Outcome::CardsTraded { troops } => Ok(troops),
+            _ => Ok(0),
+        }
+    }
+}
+
+impl Game {
+    // Applies and records the action, see history for what can be undone
+    pub fn apply(&mut self, action: Action) -> Result<Outcome, GameError> {
+        if self.is_over() { return Err(GameError::GameOver); }
+        self.record(action, Game::apply_unrecorded)
+    }
+
+    fn apply_unrecorded(&mut self, action: Action) -> Result<Outcome, GameError> {
+        match action {
+            Action::PlaceTroops { territory, troops } => self.apply_place(territory, troops),
+            Action::Attack { from, to, dice } => self.apply_attack(from, to, dice),
+            Action::AttackUntil { from, to, mode } => self.apply_attack_until(from, to, mode),
+            Action::Advance { troops } => self.apply_advance(troops),
+            Action::Fortify { from, to, troops } => self.apply_fortify(from, to, troops),
+            Action::EndPhase => self.apply_end_phase(),
+            Action::EndTurn => self.apply_end_turn(),
+            Action::TradeCards { cards } => self.apply_trade(cards),
+            Action::ChooseDice { attack, defense } =>
+                self.apply_choose_dice(attack, defense).map(|_| Outcome::DiceChosen { attack, defense }),
+            Action::Claim { territory } => self.apply_claim(territory),
+            Action::Deploy { territory, troops } => self.apply_deploy(territory, troops),
+        }
+    }
+
+    pub(crate) fn check_phase(&self, phase: TurnPhase) -> Result<(), GameError> {
+        match self.turn.phase {
+            ref p if *p == phase => Ok(()),
+            TurnPhase::PostAttackFortify => Err(GameError::AdvancePending),
+            _ => Err(GameError::WrongPhase),
+        }
+    }
+
+    pub(crate) fn check_owned(&self, territory: usize) -> Result<(), GameError> {
+        if territory >= self.map.territories.len() {
+            Err(GameError::UnknownTerritory)
+        } else if !self.on_player().territories.contains(&(territory as u32)) {
+            Err(GameError::NotOwned)
+        } else {
+            Ok(())
+        }
+    }
+
+    // Every move except leaving a territory empty
+    fn check_movable(&self, from: usize, troops: u32) -> Result<(), GameError> {
+        if troops == 0 {
+            Err(GameError::NoTroops)
+        } else if troops >= self.map.territories[from].troops {
+            Err(GameError::NotEnoughTroops)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(crate) fn check_place(&self, territory: usize, troops: u32) -> Result<(), GameError> {
+        self.check_phase(TurnPhase::Place)?;
+        if self.must_trade_cards() { return Err(GameError::MustTradeCards); }
+        self.check_owned(territory)?;
+        if troops == 0 { return Err(GameError::NoTroops); }
+        if troops > self.turn.new_troops { return Err(GameError::NotEnoughTroops); }
+        Ok(())
+    }
+
+    fn apply_place(&mut self, territory: usize, troops: u32) -> Result<Outcome, GameError> {
+        self.check_place(territory, troops)?;
+        self.map.territories[territory].add_troops(troops);
+        self.turn.new_troops -= troops;
+        Ok(Outcome::Placed { territory, troops })
+    }
+
+    fn check_attack(&self, from: usize, to: usize) -> Result<(), GameError> {
+        self.check_phase(TurnPhase::Attack)?;
+        self.check_owned(from)?;
+        if to >= self.map.territories.len() { return Err(GameError::UnknownTerritory); }
+        if self.on_player().territories.contains(&(to as u32)) { return Err(GameError::OwnTerritory); }
+        if !self.map.can_attack(from, to) { return Err(GameError::NotAdjacent); }
+        Ok(())
+    }
+
+    fn apply_attack(&mut self, from: usize, to: usize, dice: u32) -> Result<Outcome, GameError> {
+        self.check_attack(from, to)?;
+        self.check_movable(from, dice)?;
+        Ok(self.resolve_attack(from, to, dice, AttackMode::Blitz))
+    }
+
+    fn apply_attack_until(&mut self, from: usize, to: usize, mode: AttackMode) -> Result<Outcome, GameError> {
+        self.check_attack(from, to)?;
+        let troops = self.map.territories[from].troops;
+        let committed = match mode {
+            AttackMode::UntilTroopsLeft(left) => troops.saturating_sub(std::cmp::max(left, 1)),
+            _ => troops.saturating_sub(1),
+        };
+        self.check_movable(from, committed)?;
+        Ok(self.resolve_attack(from, to, committed, mode))
+    }
+
+    fn apply_advance(&mut self, troops: u32) -> Result<Outcome, GameError> {
+        let (from, to) = match (&self.turn.phase, self.turn.pending_advance) {
+            (TurnPhase::PostAttackFortify, Some(advance)) => advance,
+            _ => return Err(GameError::NoAdvancePending),
+        };
+        if troops > 0 { self.check_movable(from, troops)?; }
+        self.map.territories[from].sub_troops(troops);
+        self.map.territories[to].add_troops(troops);
+        self.turn.pending_advance = None;
+        self.unselect_all();
+        self.resume_attack();
+        Ok(Outcome::Advanced { troops })
+    }
+
+    fn apply_fortify(&mut self, from: usize, to: usize, troops: u32) -> Result<Outcome, GameError> {
+        self.check_phase(TurnPhase::Fortify)?;
+        self.check_owned(from)?;
+        self.check_owned(to)?;
+        if !self.can_fortify(from, to) { return Err(GameError::NotAdjacent); }
+        self.check_movable(from, troops)?;
+        if troops + self.fortified_troops(from) >= self.map.territories[from].troops { return Err(GameError::AlreadyMoved); }
+        self.map.territories[from].sub_troops(troops);
+        self.map.territories[to].add_troops(troops);
+        *self.turn.fortified.entry(to).or_insert(0) += troops;
+        self.unselect_all();
+        self.turn.fortifications += 1;
+        if self.config.fortify_rules.per_turn.is_some_and(|limit| self.turn.fortifications >= limit) {
+            self.start_next_turn();
+        }
+        Ok(Outcome::Fortified { troops })
+    }
+
+    fn apply_end_phase(&mut self) -> Result<Outcome, GameError> {
+        let next = match self.turn.phase {
+            TurnPhase::Place => TurnPhase::Attack,
+            TurnPhase::Attack => TurnPhase::Fortify,
+            TurnPhase::Fortify => return self.apply_end_turn(),
+            TurnPhase::PostAttackFortify => return Err(GameError::AdvancePending),
+            TurnPhase::Claim | TurnPhase::Deploy => return Err(GameError::WrongPhase),
+        };
+        self.set_phase(next.clone())?;
+        Ok(Outcome::PhaseChanged(next))
+    }
+
+    fn apply_end_turn(&mut self) -> Result<Outcome, GameError> {
+        if self.turn.phase == TurnPhase::PostAttackFortify { return Err(GameError::AdvancePending); }
+        if self.is_setup_phase() { return Err(GameError::WrongPhase); }
+        if self.must_trade_cards() { return Err(GameError::MustTradeCards); }
+        if self.turn.new_troops > 0 { return Err(GameError::UnplacedTroops); }
+        self.start_next_turn();
+        Ok(Outcome::TurnEnded { next_player: self.on_player_index() })
+    }
+
+    fn apply_trade(&mut self, cards: Vec<usize>) -> Result<Outcome, GameError> {
+        self.check_phase(TurnPhase::Place)?;
+        let mut indices = cards;
+        indices.sort_unstable();
+        indices.dedup();
+        let hand = &self.on_player().cards;
+        if indices.iter().any(|i| *i >= hand.len()) { return Err(GameError::InvalidCards); }
+        let set: Vec<Card> = indices.iter().map(|i| hand[*i]).collect();
+        if !is_set(&set) { return Err(GameError::NotASet); }
+        let troops = self.trade_set(&indices);
+        Ok(Outcome::CardsTraded { troops })
+    }
+
+    // Phase changes requested by the player rather than driven by the game
+    pub(crate) fn set_phase(&mut self, phase: TurnPhase) -> Result<(), GameError> {
+        if self.is_over() { return Err(GameError::GameOver); }
+        if self.turn.phase == TurnPhase::PostAttackFortify { return Err(GameError::AdvancePending); }
+        if !self.turn.phase.can_transition_to(&phase) { return Err(GameError::IllegalPhaseChange); }
+        if phase != TurnPhase::Place {
+            if self.must_trade_cards() { return Err(GameError::MustTradeCards); }
+            if self.turn.new_troops > 0 { return Err(GameError::UnplacedTroops); }
+        }
+        self.turn.phase = phase;
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{Game, TurnPhase};
+use crate::action::{Action, GameError, Outcome};
+use crate::cards::find_set;
+use crate::odds::odds_with;
+
+pub mod random;
+pub mod greedy;
+pub mod turtle;
+pub mod mcts;
+
+pub use self::random::RandomBot;
+pub use self::greedy::GreedyBot;
+pub use self::turtle::TurtleBot;
+pub use self::mcts::MctsBot;
+
+// Upper bound on actions in one computer turn, in case an agent never ends it
+const MAX_TURN_ACTIONS: usize = 1000;
+
+// Picks the next move for whichever player is on. Agents only get to look at the game,
+// the move they return is checked and applied like any other.
+pub trait Agent {
+    fn next_action(&mut self, game: &Game) -> Action;
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Bot {
+    // Any legal move
+    Random = 0,
+    // Best odds attacks, finishing off countries
+    Greedy = 1,
+    // Builds up on the borders and only attacks with overwhelming odds
+    Turtle = 2,
+    // Tree search over simulated games, see MctsBot
+    Mcts = 3,
+}
+
+impl Bot {
+    pub fn agent(&self, seed: u64) -> Box<dyn Agent> {
+        match self {
+            Bot::Random => Box::new(RandomBot::new(seed)),
+            Bot::Greedy => Box::new(GreedyBot),
+            Bot::Turtle => Box::new(TurtleBot),
+            Bot::Mcts => Box::new(MctsBot::new(seed)),
+        }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Game {
+    pub fn player_bot(&self, index: usize) -> Option<Bot> { self.players[index].bot }
+    pub fn set_player_bot(&mut self, index: usize, bot: Option<Bot>) -> () { self.players[index].bot = bot; }
+    pub fn is_ai_turn(&self) -> bool { !self.is_over() && self.on_player().bot.is_some() }
+
+    // One move by the computer player that is on
+    pub fn play_ai_action(&mut self) -> Result<(), GameError> {
+        let mut agent = self.on_player_agent().ok_or(GameError::WrongPhase)?;
+        self.play_agent_action(agent.as_mut()).map(|_| ())
+    }
+    // Moves for the computer player until its turn is over
+    pub fn play_ai_turn(&mut self) -> Result<(), GameError> {
+        let mut agent = self.on_player_agent().ok_or(GameError::WrongPhase)?;
+        self.play_agent_turn(agent.as_mut())
+    }
+    // Plays the on player's turn with a tree search bot, stopping each search after the given
+    // number of iterations or milliseconds (0 for no time limit)
+    pub fn play_mcts_turn(&mut self, iterations: u32, time_limit_ms: u32) -> Result<(), GameError> {
+        let mut bot = MctsBot::new(self.agent_seed()).iterations(iterations);
+        if time_limit_ms > 0 { bot = bot.time_limit_ms(time_limit_ms); }
+        self.play_agent_turn(&mut bot)
+    }
+}
+
+impl Game {
+    // Seeded apart from the dice, so bots don't change what gets rolled
+    fn agent_seed(&self) -> u64 {
+        self.config.seed.wrapping_add(self.action_count() as u64)
+    }
+    fn on_player_agent(&self) -> Option<Box<dyn Agent>> {
+        self.on_player().bot.map(|bot| bot.agent(self.agent_seed()))
+    }
+
+    pub fn play_agent_action(&mut self, agent: &mut dyn Agent) -> Result<Outcome, GameError> {
+        let action = agent.next_action(self);
+        self.apply(action)
+    }
+
+    pub fn play_agent_turn(&mut self, agent: &mut dyn Agent) -> Result<(), GameError> {
+        // Setup moves pass play on without starting a new turn
+        let (turn, player) = (self.turn_number(), self.on_player_index());
+        for _ in 0..MAX_TURN_ACTIONS {
+            if self.is_over() || self.turn_number() != turn || self.on_player_index() != player { return Ok(()); }
+            self.play_agent_action(agent)?;
+        }
+        self.apply(Action::EndTurn).map(|_| ())
+    }
+
+    // A representative set of legal moves for the on player: troop counts are limited to
+    // one or all of them rather than every possible number
+    pub fn legal_actions(&self) -> Vec<Action> {
+        if self.is_over() { return vec!(); }
+        let mut actions = vec!();
+        match self.turn.phase {
+            TurnPhase::Place => {
+                if let Some(cards) = find_set(&self.on_player().cards) {
+                    actions.push(Action::TradeCards { cards });
+                }
+                if self.must_trade_cards() { return actions; }
+                let troops = self.turn.new_troops;
+                for territory in owned(self) {
+                    if troops > 0 { actions.push(Action::PlaceTroops { territory, troops }); }
+                    if troops > 1 { actions.push(Action::PlaceTroops { territory, troops: 1 }); }
+                }
+                if troops == 0 { actions.push(Action::EndPhase); }
+            },
+            TurnPhase::Attack => {
+                for (from, to) in attack_options(self) {
+                    actions.push(Action::Attack { from, to, dice: troops_on(self, from) - 1 });
+                }
+                actions.push(Action::EndPhase);
+            },
+            TurnPhase::PostAttackFortify => {
+                actions.push(Action::Advance { troops: 0 });
+                if let Some((from, _)) = self.turn.pending_advance {
+                    actions.push(Action::Advance { troops: troops_on(self, from) - 1 });
+                }
+            },
+            TurnPhase::Fortify => {
+                for (from, to) in fortify_options(self) {
+                    actions.push(Action::Fortify { from, to, troops: fortifiable(self, from) });
+                }
+                actions.push(Action::EndPhase);
+            },
+            TurnPhase::Claim => {
+                for territory in self.unclaimed_territories() {
+                    actions.push(Action::Claim { territory: territory as usize });
+                }
+            },
+            TurnPhase::Deploy => {
+                let troops = self.deploy_size();
+                for territory in owned(self) {
+                    actions.push(Action::Deploy { territory, troops });
+                }
+            },
+        }
+        actions
+    }
+}
+
+pub(crate) fn owned(game: &Game) -> Vec<usize> {
+    game.on_player().territories.iter().map(|t| *t as usize).collect()
+}
+
+pub(crate) fn troops_on(game: &Game, territory: usize) -> u32 {
+    game.map.territories[territory].troops
+}
+
+pub(crate) fn enemy_neighbors(game: &Game, territory: usize) -> Vec<usize> {
+    let player = game.on_player();
+    game.map.neighbors(territory).iter()
+        .filter(|n| !player.territories.contains(n))
+        .map(|n| *n as usize)
+        .collect()
+}
+
+// Neighbors the on player already owns
+pub(crate) fn owned_neighbors(game: &Game, territory: usize) -> usize {
+    let player = game.on_player();
+    game.map.neighbors(territory).iter().filter(|n| player.territories.contains(n)).count()
+}
+
+pub(crate) fn is_border(game: &Game, territory: usize) -> bool {
+    !enemy_neighbors(game, territory).is_empty()
+}
+
+// Enemy troops that could attack the territory next turn
+pub(crate) fn threat_to(game: &Game, territory: usize) -> u32 {
+    enemy_neighbors(game, territory).iter()
+        .filter(|n| game.map.can_attack(**n, territory))
+        .map(|n| troops_on(game, *n))
+        .sum()
+}
+
+// Chance of taking the territory attacking with everything, under the game's dice rules
+pub(crate) fn win_chance(game: &Game, from: usize, to: usize) -> f64 {
+    let defender = game.territory_owner(to).map(|p| p as usize);
+    let rules = game.battle_dice(game.on_player_index(), defender);
+    odds_with(&rules, troops_on(game, from) - 1, troops_on(game, to)).attacker_win()
+}
+
+// (from, to) pairs the on player can attack with at least one troop
+pub(crate) fn attack_options(game: &Game) -> Vec<(usize, usize)> {
+    owned(game).into_iter()
+        .filter(|from| troops_on(game, *from) > 1)
+        .flat_map(|from| enemy_neighbors(game, from).into_iter()
+            .filter(move |to| game.map.can_attack(from, *to))
+            .map(move |to| (from, to)))
+        .collect()
+}
+
+// Troops that can leave the territory when fortifying
+pub(crate) fn fortifiable(game: &Game, territory: usize) -> u32 {
+    troops_on(game, territory).saturating_sub(game.fortified_troops(territory) + 1)
+}
+
+// (from, to) pairs of owned territories with troops to spare, as far as the fortify reach rule allows
+pub(crate) fn fortify_options(game: &Game) -> Vec<(usize, usize)> {
+    owned(game).into_iter()
+        .filter(|from| fortifiable(game, *from) > 0)
+        .flat_map(|from| game.fortify_targets(from).into_iter().map(move |to| (from, to as usize)))
+        .collect()
+}
+
+// Moves every bot has to make the same way: trading in a set and ending an empty place phase
+pub(crate) fn forced_action(game: &Game) -> Option<Action> {
+    if game.turn.phase != TurnPhase::Place { return None; }
+    if let Some(cards) = find_set(&game.on_player().cards) {
+        return Some(Action::TradeCards { cards });
+    }
+    if game.turn.new_troops == 0 { Some(Action::EndPhase) } else { None }
+}
+
+// Moves the spare troops of an interior territory up to the most threatened border it can reach
+pub(crate) fn consolidate(game: &Game) -> Option<Action> {
+    fortify_options(game).into_iter()
+        .filter(|(from, to)| !is_border(game, *from) && is_border(game, *to))
+        .max_by_key(|(from, to)| (troops_on(game, *from), threat_to(game, *to)))
+        .map(|(from, to)| Action::Fortify { from, to, troops: fortifiable(game, from) })
+}
@@ -0,0 +1,87 @@
+use crate::{Game, TurnPhase};
+use crate::action::Action;
+use crate::ai::{attack_options, consolidate, forced_action, is_border, owned, owned_neighbors, troops_on, win_chance, Agent};
+
+// Attacks it is at least this likely to win
+const MIN_ODDS: f64 = 0.6;
+
+// Goes after the country it is closest to holding, attacking wherever its odds are best
+pub struct GreedyBot;
+
+impl GreedyBot {
+    // The unheld country with the largest share of its territories owned, bigger bonus first on ties
+    fn target_country(game: &Game) -> Option<usize> {
+        let player = game.on_player();
+        game.map.countries.iter().enumerate()
+            .filter(|(_, c)| !c.territories.is_empty() && !c.is_held_by(player))
+            .max_by_key(|(_, c)| {
+                let held = c.territories.iter().filter(|t| player.territories.contains(t)).count();
+                (held * 100 / c.territories.len(), c.bonus)
+            })
+            .map(|(i, _)| i)
+    }
+
+    fn in_country(game: &Game, country: Option<usize>, territory: usize) -> bool {
+        country.is_some_and(|c| game.map.countries[c].territories.contains(&(territory as u32)))
+    }
+
+    // Where new troops go: the strongest territory attacking into the target country
+    fn front(game: &Game) -> usize {
+        let country = GreedyBot::target_country(game);
+        attack_options(game).into_iter().chain(
+                owned(game).into_iter().filter(|t| is_border(game, *t)).map(|t| (t, t)))
+            .max_by_key(|(from, to)| (GreedyBot::in_country(game, country, *to), troops_on(game, *from)))
+            .map(|(from, _)| from)
+            .or_else(|| owned(game).first().copied())
+            .unwrap_or(0)
+    }
+
+    // Builds out from what it already has, towards the biggest bonuses
+    fn claim(game: &Game) -> Action {
+        let territory = game.unclaimed_territories().into_iter()
+            .map(|t| t as usize)
+            .max_by_key(|t| {
+                let bonus = game.map.countries.iter().find(|c| c.territories.contains(&(*t as u32))).map(|c| c.bonus);
+                (owned_neighbors(game, *t), bonus)
+            })
+            .unwrap_or(0);
+        Action::Claim { territory }
+    }
+
+    // Attacks with good odds, preferring the target country and then the best odds
+    fn attack(game: &Game) -> Action {
+        let country = GreedyBot::target_country(game);
+        attack_options(game).into_iter()
+            .map(|(from, to)| (from, to, win_chance(game, from, to)))
+            .filter(|(_, _, win)| *win >= MIN_ODDS)
+            .max_by(|a, b| (GreedyBot::in_country(game, country, a.1), a.2)
+                .partial_cmp(&(GreedyBot::in_country(game, country, b.1), b.2))
+                .unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(from, to, _)| Action::Attack { from, to, dice: troops_on(game, from) - 1 })
+            .unwrap_or(Action::EndPhase)
+    }
+
+    // Keeps pushing forward, leaving half behind if the old territory is still on a border
+    fn advance(game: &Game) -> Action {
+        let troops = match game.turn.pending_advance {
+            Some((from, _)) if is_border(game, from) => (troops_on(game, from) - 1) / 2,
+            Some((from, _)) => troops_on(game, from) - 1,
+            None => 0,
+        };
+        Action::Advance { troops }
+    }
+}
+
+impl Agent for GreedyBot {
+    fn next_action(&mut self, game: &Game) -> Action {
+        if let Some(action) = forced_action(game) { return action; }
+        match game.turn.phase {
+            TurnPhase::Place => Action::PlaceTroops { territory: GreedyBot::front(game), troops: game.turn.new_troops },
+            TurnPhase::Attack => GreedyBot::attack(game),
+            TurnPhase::PostAttackFortify => GreedyBot::advance(game),
+            TurnPhase::Fortify => consolidate(game).unwrap_or(Action::EndPhase),
+            TurnPhase::Claim => GreedyBot::claim(game),
+            TurnPhase::Deploy => Action::Deploy { territory: GreedyBot::front(game), troops: game.deploy_size() },
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use rand::{Rng, RngCore};
+
+use crate::Game;
+use crate::action::Action;
+use crate::ai::{Agent, GreedyBot};
+use crate::history::History;
+use crate::rng::GameRng;
+
+const DEFAULT_ITERATIONS: u32 = 200;
+const DEFAULT_ROLLOUT_ACTIONS: u32 = 40;
+const DEFAULT_EXPLORATION: f64 = 1.4;
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn now_ms() -> f64 { js_sys::Date::now() }
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn now_ms() -> f64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs_f64() * 1000.0)
+        .unwrap_or(0.0)
+}
+
+struct Node {
+    // The move leading here and the player who made it, None for the root
+    action: Option<(Action, usize)>,
+    children: Vec<usize>,
+    visits: u32,
+    // Summed scores for the player who made the move
+    score: f64,
+}
+
+impl Node {
+    fn new(action: Option<(Action, usize)>) -> Node {
+        Node { action, children: vec!(), visits: 0, score: 0.0 }
+    }
+}
+
+// Monte Carlo tree search over the moves from legal_actions. Dice are handled by determinization:
+// every iteration plays on a copy of the game with freshly seeded dice, and the tree is open loop,
+// keyed by moves rather than states, so a branch only offers the moves legal in that sample.
+// Runs single threaded so it works the same in wasm.
+pub struct MctsBot {
+    rng: GameRng,
+    iterations: u32,
+    time_limit_ms: Option<f64>,
+    rollout_actions: u32,
+    exploration: f64,
+}
+
+impl MctsBot {
+    pub fn new(seed: u64) -> MctsBot {
+        MctsBot {
+            rng: GameRng::seed_from_u64(seed),
+            iterations: DEFAULT_ITERATIONS,
+            time_limit_ms: None,
+            rollout_actions: DEFAULT_ROLLOUT_ACTIONS,
+            exploration: DEFAULT_EXPLORATION,
+        }
+    }
+
+    pub fn iterations(mut self, iterations: u32) -> MctsBot {
+        self.iterations = iterations;
+        self
+    }
+    // Stops searching after this long even if iterations remain
+    pub fn time_limit_ms(mut self, ms: u32) -> MctsBot {
+        self.time_limit_ms = Some(ms as f64);
+        self
+    }
+    // Greedy moves played past the tree before scoring
+    pub fn rollout_actions(mut self, actions: u32) -> MctsBot {
+        self.rollout_actions = actions;
+        self
+    }
+    pub fn exploration(mut self, exploration: f64) -> MctsBot {
+        self.exploration = exploration;
+        self
+    }
+
+    // A copy of the game with new dice and no history, cheap to clone and play ahead on
+    fn sample(&mut self, game: &Game) -> Game {
+        Game {
+            map: game.map.clone(),
+            players: game.players.clone(),
+            turn: game.turn.clone(),
+            deck: game.deck.clone(),
+            config: game.config.clone(),
+            eliminations: game.eliminations.clone(),
+            history: History::disabled(),
+            rng: GameRng::seed_from_u64(self.rng.next_u64()),
+        }
+    }
+
+    fn ucb(&self, parent: &Node, child: &Node) -> f64 {
+        if child.visits == 0 { return f64::INFINITY; }
+        let visits = child.visits as f64;
+        child.score / visits + self.exploration * ((parent.visits as f64).ln() / visits).sqrt()
+    }
+
+    fn iterate(&mut self, tree: &mut Vec<Node>, root: &Game) -> () {
+        let mut game = self.sample(root);
+        let mut node = 0;
+        let mut path = vec!(0);
+        while !game.is_over() {
+            let legal = game.legal_actions();
+            let untried: Vec<&Action> = legal.iter()
+                .filter(|a| !tree[node].children.iter().any(|c| tree[*c].action.as_ref().is_some_and(|(b, _)| b == *a)))
+                .collect();
+            let player = game.on_player_index();
+            if !untried.is_empty() {
+                let action = untried[self.rng.gen_range(0, untried.len())].clone();
+                if game.apply(action.clone()).is_err() { break; }
+                tree.push(Node::new(Some((action, player))));
+                let child = tree.len() - 1;
+                tree[node].children.push(child);
+                path.push(child);
+                break;
+            }
+            let best = tree[node].children.iter()
+                .filter(|c| tree[**c].action.as_ref().is_some_and(|(a, _)| legal.contains(a)))
+                .max_by(|a, b| self.ucb(&tree[node], &tree[**a]).total_cmp(&self.ucb(&tree[node], &tree[**b])))
+                .copied();
+            match best {
+                Some(child) => {
+                    let (action, _) = tree[child].action.clone().expect("only the root has no action");
+                    if game.apply(action).is_err() { break; }
+                    node = child;
+                    path.push(child);
+                }
+                None => break,
+            }
+        }
+
+        for _ in 0..self.rollout_actions {
+            if game.is_over() || game.play_agent_action(&mut GreedyBot).is_err() { break; }
+        }
+        let scores = evaluate(&game);
+        for n in path {
+            tree[n].visits += 1;
+            if let Some((_, player)) = tree[n].action { tree[n].score += scores[player]; }
+        }
+    }
+
+    pub fn search(&mut self, game: &Game) -> Option<Action> {
+        let legal = game.legal_actions();
+        if legal.len() <= 1 { return legal.into_iter().next(); }
+        let started = now_ms();
+        let mut tree = vec!(Node::new(None));
+        for _ in 0..self.iterations {
+            if self.time_limit_ms.is_some_and(|limit| now_ms() - started >= limit) { break; }
+            self.iterate(&mut tree, game);
+        }
+        tree[0].children.iter()
+            .max_by_key(|c| tree[**c].visits)
+            .and_then(|c| tree[*c].action.clone())
+            .map(|(action, _)| action)
+    }
+}
+
+// Between 0 and 1 for each player: 1 for the winner of a finished game, otherwise an even mix
+// of their share of the territories and of the troops
+fn evaluate(game: &Game) -> Vec<f64> {
+    let territories = game.map.territories.len().max(1) as f64;
+    let troops = game.map.territories.iter().map(|t| t.troops).sum::<u32>().max(1) as f64;
+    game.players.iter().map(|p| {
+        if game.is_over() { return if p.is_eliminated() { 0.0 } else { 1.0 }; }
+        let held_troops: u32 = p.territories.iter().map(|t| game.map.territories[*t as usize].troops).sum();
+        0.5 * p.territories.len() as f64 / territories + 0.5 * held_troops as f64 / troops
+    }).collect()
+}
+
+impl Agent for MctsBot {
+    fn next_action(&mut self, game: &Game) -> Action {
+        self.search(game).unwrap_or(Action::EndTurn)
+    }
+}
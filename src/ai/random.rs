@@ -0,0 +1,25 @@
+use rand::Rng;
+
+use crate::Game;
+use crate::action::Action;
+use crate::ai::Agent;
+use crate::rng::GameRng;
+
+pub struct RandomBot {
+    rng: GameRng,
+}
+
+impl RandomBot {
+    pub fn new(seed: u64) -> RandomBot {
+        RandomBot { rng: GameRng::seed_from_u64(seed) }
+    }
+}
+
+impl Agent for RandomBot {
+    fn next_action(&mut self, game: &Game) -> Action {
+        let mut actions = game.legal_actions();
+        if actions.is_empty() { return Action::EndTurn; }
+        let choice = self.rng.gen_range(0, actions.len());
+        actions.swap_remove(choice)
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{Game, TurnPhase};
+use crate::action::Action;
+use crate::ai::{attack_options, consolidate, forced_action, is_border, owned, owned_neighbors, threat_to, troops_on, win_chance, Agent};
+
+// Attacks it is at least this likely to win
+const MIN_ODDS: f64 = 0.9;
+
+// Stacks troops where the enemy is strongest and only attacks when it can hardly lose
+pub struct TurtleBot;
+
+impl TurtleBot {
+    // The border territory most outnumbered by the enemies next to it
+    fn weakest_border(game: &Game) -> usize {
+        owned(game).into_iter()
+            .filter(|t| is_border(game, *t))
+            .max_by_key(|t| threat_to(game, *t) as i64 - troops_on(game, *t) as i64)
+            .or_else(|| owned(game).first().copied())
+            .unwrap_or(0)
+    }
+
+    // Keeps its territories together, away from the other players
+    fn claim(game: &Game) -> Action {
+        let territory = game.unclaimed_territories().into_iter()
+            .map(|t| t as usize)
+            .max_by_key(|t| {
+                let enemies = game.map.neighbors(*t).iter()
+                    .filter(|n| game.territory_owner(**n as usize).is_some_and(|p| p as usize != game.on_player_index()))
+                    .count();
+                owned_neighbors(game, *t) as i64 - enemies as i64
+            })
+            .unwrap_or(0);
+        Action::Claim { territory }
+    }
+
+    fn attack(game: &Game) -> Action {
+        attack_options(game).into_iter()
+            .map(|(from, to)| (from, to, win_chance(game, from, to)))
+            .filter(|(_, _, win)| *win >= MIN_ODDS)
+            .max_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(from, to, _)| Action::Attack { from, to, dice: troops_on(game, from) - 1 })
+            .unwrap_or(Action::EndPhase)
+    }
+
+    // Keeps the old territory's troops home unless it no longer touches an enemy
+    fn advance(game: &Game) -> Action {
+        let troops = match game.turn.pending_advance {
+            Some((from, _)) if !is_border(game, from) => troops_on(game, from) - 1,
+            _ => 0,
+        };
+        Action::Advance { troops }
+    }
+}
+
+impl Agent for TurtleBot {
+    fn next_action(&mut self, game: &Game) -> Action {
+        if let Some(action) = forced_action(game) { return action; }
+        match game.turn.phase {
+            TurnPhase::Place => Action::PlaceTroops { territory: TurtleBot::weakest_border(game), troops: game.turn.new_troops },
+            TurnPhase::Attack => TurtleBot::attack(game),
+            TurnPhase::PostAttackFortify => TurtleBot::advance(game),
+            TurnPhase::Fortify => consolidate(game).unwrap_or(Action::EndPhase),
+            TurnPhase::Claim => TurtleBot::claim(game),
+            TurnPhase::Deploy => Action::Deploy { territory: TurtleBot::weakest_border(game), troops: game.deploy_size() },
+        }
+    }
+}
@@ -0,0 +1,110 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::Game;
+use crate::action::{Action, GameError, Outcome};
+
+// How long an attack keeps rolling. Every mode also stops once the territory falls
+// or the attackers run out.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AttackMode {
+    // One exchange of dice
+    SingleRoll,
+    // Until the attacking territory is down to this many troops
+    UntilTroopsLeft(u32),
+    // Until the chance of taking the territory drops below this percentage
+    UntilOddsBelow(u32),
+    // Everything but the troop that has to stay behind
+    Blitz,
+}
+
+// One exchange of dice
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Roll {
+    pub attack_dice: u32,
+    pub defense_dice: u32,
+    pub attacker_losses: u32,
+    pub defender_losses: u32,
+    // Troops still fighting on each side after the roll
+    pub attackers_left: u32,
+    pub defenders_left: u32,
+    // Face values, highest first, so attack_faces[i] was compared with defense_faces[i]
+    pub(crate) attack_faces: Vec<u8>,
+    pub(crate) defense_faces: Vec<u8>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Roll {
+    pub fn attack_faces(&self) -> Vec<u8> { self.attack_faces.clone() }
+    pub fn defense_faces(&self) -> Vec<u8> { self.defense_faces.clone() }
+}
+
+// An attack roll by roll, for the UI to animate and the history to show
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BattleLog {
+    // Position of the attack in the game's action history
+    pub action: usize,
+    pub turn: u32,
+    pub player: u32,
+    pub from: usize,
+    pub to: usize,
+    pub captured: bool,
+    pub(crate) rolls: Vec<Roll>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl BattleLog {
+    pub fn rolls(&self) -> Vec<Roll> { self.rolls.clone() }
+    pub fn attacker_losses(&self) -> u32 { self.rolls.iter().map(|r| r.attacker_losses).sum() }
+    pub fn defender_losses(&self) -> u32 { self.rolls.iter().map(|r| r.defender_losses).sum() }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Game {
+    pub fn attack_single_roll(&mut self, from: usize, to: usize) -> Result<BattleLog, GameError> {
+        self.attack_until(from, to, AttackMode::SingleRoll)
+    }
+    pub fn attack_until_troops_left(&mut self, from: usize, to: usize, troops: u32) -> Result<BattleLog, GameError> {
+        self.attack_until(from, to, AttackMode::UntilTroopsLeft(troops))
+    }
+    pub fn attack_until_odds_below(&mut self, from: usize, to: usize, percent: u32) -> Result<BattleLog, GameError> {
+        self.attack_until(from, to, AttackMode::UntilOddsBelow(percent))
+    }
+    pub fn blitz(&mut self, from: usize, to: usize) -> Result<BattleLog, GameError> {
+        self.attack_until(from, to, AttackMode::Blitz)
+    }
+}
+
+impl BattleLog {
+    // Called once the action has been applied
+    pub(crate) fn from_outcome(game: &Game, action_index: usize, action: &Action, outcome: &Outcome) -> Option<BattleLog> {
+        let (from, to) = match action {
+            Action::Attack { from, to, .. } | Action::AttackUntil { from, to, .. } => (*from, *to),
+            _ => return None,
+        };
+        match outcome {
+            Outcome::Battle { captured, rolls, .. } => Some(BattleLog {
+                action: action_index,
+                turn: game.turn_number(),
+                player: game.on_player_index() as u32,
+                from,
+                to,
+                captured: *captured,
+                rolls: rolls.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Game {
+    pub fn attack_until(&mut self, from: usize, to: usize, mode: AttackMode) -> Result<BattleLog, GameError> {
+        let action = Action::AttackUntil { from, to, mode };
+        let action_index = self.action_count();
+        let outcome = self.apply(action.clone())?;
+        Ok(BattleLog::from_outcome(self, action_index, &action, &outcome).expect("attacks always end in a battle"))
+    }
+}
@@ -0,0 +1,227 @@
+// Plays a game in the terminal, e.g.
+// cargo run --no-default-features --bin rust-wars-cli -- --seed 7 --players 3 --bot 1
+// --army <troops> deals the territories and has everyone deploy that many troops first,
+// --draft <troops> does the same but with players claiming territories in turn,
+// --neutral <troops> adds a neutral third army with that many troops on each of its territories,
+// --map <file> plays on a map file such as maps/tiny.json, or on a WarGear board export ending in .xml,
+// --generate <seed> plays on a random map made from the seed
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+use rust_wars::{Action, AttackMode, BorderKind, Bot, Game, GameConfig, Map, MapGenerator, Outcome, TerritoryDeal};
+
+const HELP: &str = "\
+commands:
+  map                          show every territory
+  claim <territory>            take an unowned territory during the draft
+  deploy <territory> <troops>  place starting troops
+  place <territory> <troops>   place new troops
+  attack <from> <to> <troops>  attack with up to <troops> troops
+  roll <from> <to>             roll the dice once
+  until <from> <to> <troops>   attack until <troops> are left behind
+  odds <from> <to> <percent>   attack until the odds drop below <percent>
+  blitz <from> <to>            attack with everything
+  advance <troops>             move extra troops into a captured territory
+  fortify <from> <to> <troops> move troops between your territories
+  dice <attack> <defense>      choose how many dice you roll
+  hand                         list your cards
+  trade <card> <card> <card>   trade a set of cards by hand position
+  end                          finish the current phase
+  endturn                      finish the turn
+  undo, redo                   step back or forward through your actions
+  save <file>, load <file>     write or read the game as JSON
+  help                         show this list
+  quit                         leave the game";
+
+fn main() {
+    let mut config = GameConfig::new();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--map" {
+            let loaded = args.next().ok_or_else(|| String::from("--map needs a file"))
+                .and_then(|path| fs::read_to_string(&path).map(|text| (path, text)).map_err(|e| e.to_string()))
+                .and_then(|(path, text)| if path.ends_with(".xml") {
+                    let import = Map::from_wargear(&text).map_err(|e| e.to_string())?;
+                    import.unsupported().iter().for_each(|u| eprintln!("not supported: {}", u));
+                    Ok(import.map())
+                } else {
+                    Map::from_json(&text).map_err(|e| e.to_string())
+                });
+            match loaded {
+                Ok(map) => {
+                    map.validate().iter().for_each(|p| eprintln!("warning: {}", p));
+                    config = config.map(map);
+                }
+                Err(e) => { eprintln!("{}", e); return; }
+            }
+            continue;
+        }
+        let value = args.next().and_then(|v| v.parse::<u64>().ok());
+        match (arg.as_str(), value) {
+            ("--seed", Some(seed)) => config = config.seed(seed),
+            ("--players", Some(count)) => config = config.player_count(count as usize),
+            ("--bot", Some(player)) => config = config.player_bot(player as usize, Bot::Greedy),
+            ("--army", Some(troops)) => config = config.starting_army(troops as u32),
+            ("--draft", Some(troops)) => config = config.starting_army(troops as u32).territory_deal(TerritoryDeal::Draft),
+            ("--neutral", Some(troops)) => config = config.neutral_army(troops as u32),
+            ("--generate", Some(seed)) => match MapGenerator::new().seed(seed).generate() {
+                Ok(map) => config = config.map(map),
+                Err(e) => { eprintln!("{}", e); return; }
+            },
+            _ => {
+                eprintln!("usage: rust-wars-cli [--seed <n>] [--players <n>] [--army <n> | --draft <n>] [--neutral <n>] [--map <file> | --generate <seed>] [--bot <player>]...");
+                return;
+            }
+        }
+    }
+    let mut game = match Game::with_config(config) {
+        Ok(game) => game,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+
+    print_map(&game);
+    println!("type `help` for a list of commands");
+    let stdin = io::stdin();
+    loop {
+        if game.is_over() {
+            println!("{} wins!", game.player_name(game.active_players()[0]));
+            return;
+        }
+        if game.is_ai_turn() {
+            let name = game.player_name(game.on_player_index());
+            match game.play_ai_turn() {
+                Ok(()) => println!("{} took their turn", name),
+                Err(e) => { println!("error: {}", e); return; }
+            }
+            continue;
+        }
+        let to_place = if game.is_setup_phase() {
+            game.troops_to_deploy(game.on_player_index())
+        } else {
+            game.troops_available_for_placement()
+        };
+        print!("{} ({:?}, {} troops to place)> ", game.player_name(game.on_player_index()), game.turn_phase(), to_place);
+        io::stdout().flush().ok();
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 { return; }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let numbers: Vec<usize> = words.iter().skip(1).filter_map(|w| w.parse().ok()).collect();
+        let action = match (words.first().copied(), numbers.as_slice()) {
+            (None, _) => continue,
+            (Some("quit"), _) | (Some("exit"), _) => return,
+            (Some("help"), _) => { println!("{}", HELP); continue; }
+            (Some("map"), _) => { print_map(&game); continue; }
+            (Some("hand"), _) => { print_hand(&game); continue; }
+            (Some("undo"), _) => { report(game.undo().map(|_| "undone".to_string())); continue; }
+            (Some("redo"), _) => { report(game.redo_action().map(describe)); continue; }
+            (Some("save"), _) if words.len() == 2 => {
+                report(game.to_json().map_err(|e| e.to_string())
+                    .and_then(|json| fs::write(words[1], json).map_err(|e| e.to_string()))
+                    .map(|_| format!("saved to {}", words[1])));
+                continue;
+            }
+            (Some("load"), _) if words.len() == 2 => {
+                let loaded = fs::read_to_string(words[1]).map_err(|e| e.to_string())
+                    .and_then(|json| Game::from_json(&json).map_err(|e| e.to_string()));
+                match loaded {
+                    Ok(g) => { game = g; print_map(&game); }
+                    Err(e) => println!("error: {}", e),
+                }
+                continue;
+            }
+            (Some("claim"), [territory]) => Action::Claim { territory: *territory },
+            (Some("deploy"), [territory, troops]) => Action::Deploy { territory: *territory, troops: *troops as u32 },
+            (Some("place"), [territory, troops]) =>
+                Action::PlaceTroops { territory: *territory, troops: *troops as u32 },
+            (Some("attack"), [from, to, dice]) => Action::Attack { from: *from, to: *to, dice: *dice as u32 },
+            (Some("roll"), [from, to]) => Action::AttackUntil { from: *from, to: *to, mode: AttackMode::SingleRoll },
+            (Some("until"), [from, to, left]) =>
+                Action::AttackUntil { from: *from, to: *to, mode: AttackMode::UntilTroopsLeft(*left as u32) },
+            (Some("odds"), [from, to, percent]) =>
+                Action::AttackUntil { from: *from, to: *to, mode: AttackMode::UntilOddsBelow(*percent as u32) },
+            (Some("blitz"), [from, to]) => Action::AttackUntil { from: *from, to: *to, mode: AttackMode::Blitz },
+            (Some("advance"), [troops]) => Action::Advance { troops: *troops as u32 },
+            (Some("fortify"), [from, to, troops]) =>
+                Action::Fortify { from: *from, to: *to, troops: *troops as u32 },
+            (Some("trade"), cards) if !cards.is_empty() => Action::TradeCards { cards: cards.to_vec() },
+            (Some("dice"), [attack, defense]) => Action::ChooseDice { attack: *attack as u32, defense: *defense as u32 },
+            (Some("end"), _) => Action::EndPhase,
+            (Some("endturn"), _) => Action::EndTurn,
+            _ => {
+                println!("unknown command, type `help` for a list of commands");
+                continue;
+            }
+        };
+        report(game.apply(action).map(describe));
+    }
+}
+
+fn report<E: std::fmt::Display>(result: Result<String, E>) {
+    match result {
+        Ok(message) => println!("{}", message),
+        Err(e) => println!("error: {}", e),
+    }
+}
+
+fn describe(outcome: Outcome) -> String {
+    match outcome {
+        Outcome::Placed { territory, troops } => format!("placed {} on {}", troops, territory),
+        Outcome::Battle { attacker_losses, defender_losses, captured, rolls } => {
+            let rolls: Vec<String> = rolls.iter()
+                .map(|r| format!("  {:?} vs {:?}: -{} / -{}", r.attack_faces(), r.defense_faces(), r.attacker_losses, r.defender_losses))
+                .collect();
+            format!(
+                "{}\nattacker lost {}, defender lost {}{}",
+                rolls.join("\n"), attacker_losses, defender_losses, if captured { ", territory captured" } else { "" }
+            )
+        },
+        Outcome::Advanced { troops } => format!("advanced {}", troops),
+        Outcome::Fortified { troops } => format!("moved {}", troops),
+        Outcome::PhaseChanged(phase) => format!("now in {:?}", phase),
+        Outcome::TurnEnded { next_player } => format!("turn over, player {} is up", next_player + 1),
+        Outcome::CardsTraded { troops } => format!("traded for {} troops", troops),
+        Outcome::Claimed { territory } => format!("claimed {}", territory),
+        Outcome::DiceChosen { attack, defense } => format!("rolling {} to attack and {} to defend", attack, defense),
+    }
+}
+
+fn print_map(game: &Game) {
+    let map = game.get_map();
+    let troops = map.troops();
+    println!("{:>3}  {:<12} {:<12} {:>6}  neighbors", "#", "country", "owner", "troops");
+    for (t, troops) in troops.iter().enumerate() {
+        let country = (0..map.country_count())
+            .find(|c| map.country_territories(*c).contains(&(t as u32)))
+            .map(|c| map.country_name(c))
+            .unwrap_or_default();
+        let owner = match game.territory_owner(t) {
+            Some(p) => game.player_name(p as usize),
+            None if game.is_neutral(t) => String::from("neutral"),
+            None => String::new(),
+        };
+        // a: attack only, f: fortify only, d: defend only
+        let neighbors: Vec<String> = map.borders(t).iter().map(|b| match b.kind {
+            BorderKind::Normal => b.to.to_string(),
+            BorderKind::AttackOnly => format!("{}a", b.to),
+            BorderKind::FortifyOnly => format!("{}f", b.to),
+            BorderKind::DefendOnly => format!("{}d", b.to),
+        }).collect();
+        println!("{:>3}  {:<12} {:<12} {:>6}  {}", t, country, owner, troops, neighbors.join(" "));
+    }
+}
+
+fn print_hand(game: &Game) {
+    let hand = game.hand();
+    if hand.is_empty() { println!("no cards"); }
+    for (i, card) in hand.iter().enumerate() {
+        match card.territory() {
+            Some(t) => println!("{}: {:?} ({})", i, card.symbol(), t),
+            None => println!("{}: {:?}", i, card.symbol()),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::rng::GameRng;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CardSymbol {
+    Infantry = 0,
+    Cavalry = 1,
+    Artillery = 2,
+    Wild = 3,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Card {
+    pub(crate) territory: Option<u32>,
+    pub(crate) symbol: CardSymbol,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Card {
+    pub fn new(territory: Option<u32>, symbol: CardSymbol) -> Card { Card { territory, symbol } }
+    pub fn territory(&self) -> Option<u32> { self.territory }
+    pub fn symbol(&self) -> CardSymbol { self.symbol }
+    pub fn is_wild(&self) -> bool { self.symbol == CardSymbol::Wild }
+}
+
+// How many troops the nth set traded in the game is worth
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TradeSchedule {
+    // Value depends only on the set: 3 infantry = 4, 3 cavalry = 6, 3 artillery = 8, one of each = 10.
+    // Wilds count as whatever makes the set worth the most
+    Fixed,
+    // 4, 6, 8, 10, 12, 15, then +5 for every set after
+    Increasing,
+    // Increasing, but never worth more than the cap
+    Capped(u32),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CardRules {
+    pub schedule: TradeSchedule,
+    // Extra troops placed on a traded card's territory when the trading player owns it
+    pub territory_bonus: u32,
+    // A player holding this many cards must trade before doing anything else
+    pub forced_trade_at: usize,
+    pub wild_cards: usize,
+}
+
+impl Default for CardRules {
+    fn default() -> Self {
+        CardRules {
+            schedule: TradeSchedule::Increasing,
+            territory_bonus: 2,
+            forced_trade_at: 5,
+            wild_cards: 2,
+        }
+    }
+}
+
+impl CardRules {
+    pub fn trade_value(&self, trade_number: u32, set: &[Card]) -> u32 {
+        match self.schedule {
+            TradeSchedule::Fixed => fixed_value(set),
+            TradeSchedule::Increasing => increasing_value(trade_number),
+            TradeSchedule::Capped(cap) => std::cmp::min(increasing_value(trade_number), cap),
+        }
+    }
+}
+
+fn increasing_value(trade_number: u32) -> u32 {
+    match trade_number {
+        0..=4 => 4 + 2 * trade_number,
+        n => 15 + 5 * (n - 5),
+    }
+}
+
+fn fixed_value(set: &[Card]) -> u32 {
+    let symbols: Vec<CardSymbol> = set.iter().map(|c| c.symbol).filter(|s| *s != CardSymbol::Wild).collect();
+    let matching = symbols.windows(2).all(|w| w[0] == w[1]);
+    // Wilds can only make one of each when the other cards are all different
+    let one_of_each = symbols.len() < set.len() && (symbols.len() < 2 || !matching);
+    match symbols.first() {
+        _ if one_of_each => 10,
+        Some(CardSymbol::Infantry) if matching => 4,
+        Some(CardSymbol::Cavalry) if matching => 6,
+        Some(CardSymbol::Artillery) if matching => 8,
+        _ => 10,
+    }
+}
+
+// Three cards that are all the same symbol or all different, with wilds standing in for anything
+pub fn is_set(cards: &[Card]) -> bool {
+    if cards.len() != 3 { return false; }
+    let mut symbols: Vec<CardSymbol> = cards.iter().map(|c| c.symbol).filter(|s| *s != CardSymbol::Wild).collect();
+    symbols.sort_by_key(|s| *s as u8);
+    symbols.dedup();
+    let distinct = symbols.len();
+    let wilds = cards.iter().filter(|c| c.is_wild()).count();
+    distinct <= 1 || distinct + wilds == 3
+}
+
+// Indices of the first tradeable set found in a hand, if there is one
+pub fn find_set(hand: &[Card]) -> Option<Vec<usize>> {
+    let n = hand.len();
+    for a in 0..n {
+        for b in (a + 1)..n {
+            for c in (b + 1)..n {
+                if is_set(&[hand[a], hand[b], hand[c]]) { return Some(vec![a, b, c]); }
+            }
+        }
+    }
+    None
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Deck {
+    pub(crate) draw_pile: Vec<Card>,
+    pub(crate) discard_pile: Vec<Card>,
+    pub(crate) trades: u32,
+}
+
+impl Deck {
+    pub fn new(territory_count: usize, wild_cards: usize) -> Deck {
+        let symbols = [CardSymbol::Infantry, CardSymbol::Cavalry, CardSymbol::Artillery];
+        let mut draw_pile: Vec<Card> = (0..territory_count)
+            .map(|t| Card { territory: Some(t as u32), symbol: symbols[t % symbols.len()] })
+            .collect();
+        draw_pile.extend((0..wild_cards).map(|_| Card { territory: None, symbol: CardSymbol::Wild }));
+        Deck { draw_pile, discard_pile: vec!(), trades: 0 }
+    }
+
+    pub fn draw(&mut self, rng: &mut GameRng) -> Option<Card> {
+        if self.draw_pile.is_empty() {
+            self.draw_pile.append(&mut self.discard_pile);
+        }
+        if self.draw_pile.is_empty() {
+            None
+        } else {
+            let index = rng.gen_range(0, self.draw_pile.len());
+            Some(self.draw_pile.swap_remove(index))
+        }
+    }
+
+    pub fn discard(&mut self, cards: &[Card]) -> () {
+        self.discard_pile.extend_from_slice(cards);
+    }
+
+    pub fn trades(&self) -> u32 { self.trades }
+}
@@ -0,0 +1,260 @@
+use std::fmt;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::ai::Bot;
+use crate::cards::CardRules;
+use crate::dice::DiceRules;
+use crate::fortify::{FortifyReach, FortifyRules};
+use crate::map::Map;
+use crate::save::SaveError;
+use crate::setup::{SetupRules, TerritoryDeal};
+
+pub const MIN_PLAYERS: usize = 2;
+pub const MAX_PLAYERS: usize = 8;
+
+const PLAYER_COLORS: [u32; MAX_PLAYERS] = [
+    0xAA1111, 0x11AA11, 0x1111AA, 0xAAAA11, 0xAA11AA, 0x11AAAA, 0xDD7711, 0x777777,
+];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlayerSetup {
+    pub name: String,
+    pub color: u32,
+    // None for a human player
+    #[serde(default)]
+    pub bot: Option<Bot>,
+}
+
+impl PlayerSetup {
+    fn numbered(index: usize) -> PlayerSetup {
+        PlayerSetup { name: format!("Player {}", index + 1), color: PLAYER_COLORS[index % MAX_PLAYERS], bot: None }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GameConfig {
+    pub(crate) seed: u64,
+    pub(crate) players: Vec<PlayerSetup>,
+    pub(crate) map: Map,
+    // Troops on every territory at the start, None to keep the map's own counts. Ignored with a starting army.
+    pub(crate) starting_troops: Option<u32>,
+    pub(crate) card_rules: CardRules,
+    pub(crate) fortify_rules: FortifyRules,
+    #[serde(default)]
+    pub(crate) dice_rules: DiceRules,
+    #[serde(default)]
+    pub(crate) setup_rules: SetupRules,
+    // Troops on each territory of a neutral third army, None to play without one
+    #[serde(default)]
+    pub(crate) neutral_army: Option<u32>,
+    // (territory, troops) for territories that start neutral on top of the map's own
+    #[serde(default)]
+    pub(crate) neutral_territories: Vec<(usize, u32)>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConfigError {
+    TooFewPlayers = 0,
+    TooManyPlayers = 1,
+    NotEnoughTerritories = 2,
+    InvalidDice = 3,
+    ArmyTooSmall = 4,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::TooFewPlayers => write!(f, "a game needs at least {} players", MIN_PLAYERS),
+            ConfigError::TooManyPlayers => write!(f, "a game allows at most {} players", MAX_PLAYERS),
+            ConfigError::NotEnoughTerritories => write!(f, "the map has fewer territories than players"),
+            ConfigError::InvalidDice =>
+                write!(f, "dice need 2 to {} faces, 1 to 3 attack dice and 1 to 2 defense dice", crate::dice::MAX_DIE_FACES),
+            ConfigError::ArmyTooSmall => write!(f, "the starting army can't put a troop on every territory"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl GameConfig {
+    pub fn new() -> GameConfig {
+        GameConfig {
+            seed: 123,
+            players: (0..MIN_PLAYERS).map(PlayerSetup::numbered).collect(),
+            map: Map::new(),
+            starting_troops: None,
+            card_rules: CardRules::default(),
+            fortify_rules: FortifyRules::default(),
+            dice_rules: DiceRules::default(),
+            setup_rules: SetupRules::default(),
+            neutral_army: None,
+            neutral_territories: vec!(),
+        }
+    }
+
+    pub fn seed(mut self, seed: u64) -> GameConfig {
+        self.seed = seed;
+        self
+    }
+    // Adds or drops players to reach the count, new players get a default name and color
+    pub fn player_count(mut self, count: usize) -> GameConfig {
+        self.players.truncate(count);
+        let existing = self.players.len();
+        self.players.extend((existing..count).map(PlayerSetup::numbered));
+        self
+    }
+    pub fn player_name(mut self, index: usize, name: String) -> GameConfig {
+        if let Some(p) = self.players.get_mut(index) { p.name = name; }
+        self
+    }
+    pub fn player_color(mut self, index: usize, color: u32) -> GameConfig {
+        if let Some(p) = self.players.get_mut(index) { p.color = color; }
+        self
+    }
+    pub fn player_bot(mut self, index: usize, bot: Bot) -> GameConfig {
+        if let Some(p) = self.players.get_mut(index) { p.bot = Some(bot); }
+        self
+    }
+    pub fn map(mut self, map: Map) -> GameConfig {
+        self.map = map;
+        self
+    }
+    pub fn starting_troops(mut self, troops: u32) -> GameConfig {
+        self.starting_troops = Some(troops);
+        self
+    }
+    pub fn forced_trade_at(mut self, cards: usize) -> GameConfig {
+        self.card_rules.forced_trade_at = cards;
+        self
+    }
+    pub fn territory_card_bonus(mut self, troops: u32) -> GameConfig {
+        self.card_rules.territory_bonus = troops;
+        self
+    }
+    // 0 for no limit
+    pub fn fortifications_per_turn(mut self, count: u32) -> GameConfig {
+        self.fortify_rules.per_turn = if count == 0 { None } else { Some(count) };
+        self
+    }
+    pub fn fortify_reach(mut self, reach: FortifyReach) -> GameConfig {
+        self.fortify_rules.reach = reach;
+        self
+    }
+
+    pub fn die_faces(mut self, faces: u8) -> GameConfig {
+        self.dice_rules.faces = faces;
+        self
+    }
+    pub fn max_dice(mut self, attack: u32, defense: u32) -> GameConfig {
+        self.dice_rules.attack_dice = attack;
+        self.dice_rules.defense_dice = defense;
+        self
+    }
+    pub fn ties_to_attacker(mut self, ties_to_attacker: bool) -> GameConfig {
+        self.dice_rules.ties_to_attacker = ties_to_attacker;
+        self
+    }
+    pub fn balanced_blitz(mut self, balanced_blitz: bool) -> GameConfig {
+        self.dice_rules.balanced_blitz = balanced_blitz;
+        self
+    }
+
+    pub fn territory_deal(mut self, deal: TerritoryDeal) -> GameConfig {
+        self.setup_rules.deal = deal;
+        self
+    }
+    // Troops per player, deployed before the first turn. 0 keeps the map's troop counts
+    pub fn starting_army(mut self, troops: u32) -> GameConfig {
+        self.setup_rules.army = if troops == 0 { None } else { Some(troops) };
+        self
+    }
+    pub fn troops_per_deploy(mut self, troops: u32) -> GameConfig {
+        self.setup_rules.troops_per_deploy = troops;
+        self
+    }
+
+    // The territory starts neutral with the given troops, whatever the starting troops or army
+    pub fn neutral_territory(mut self, territory: usize, troops: u32) -> GameConfig {
+        self.neutral_territories.retain(|(t, _)| *t != territory);
+        self.neutral_territories.push((territory, troops));
+        self
+    }
+    // Deals a neutral army its own share of the territories, as if it were one more player, with this
+    // many troops on each. The classic rules for two players. 0 for no neutral army
+    pub fn neutral_army(mut self, troops: u32) -> GameConfig {
+        self.neutral_army = if troops == 0 { None } else { Some(troops) };
+        self
+    }
+
+    pub fn get_seed(&self) -> u64 { self.seed }
+    pub fn get_player_count(&self) -> usize { self.players.len() }
+
+    // Everything, including rules without a builder method, as serialized by to_json
+    pub fn from_json(json: &str) -> Result<GameConfig, SaveError> {
+        Ok(serde_json::from_str(json)?)
+    }
+    pub fn to_json(&self) -> Result<String, SaveError> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+impl GameConfig {
+    pub fn card_rules(mut self, rules: CardRules) -> GameConfig {
+        self.card_rules = rules;
+        self
+    }
+    pub fn fortify_rules(mut self, rules: FortifyRules) -> GameConfig {
+        self.fortify_rules = rules;
+        self
+    }
+    pub fn dice_rules(mut self, rules: DiceRules) -> GameConfig {
+        self.dice_rules = rules;
+        self
+    }
+    pub fn setup_rules(mut self, rules: SetupRules) -> GameConfig {
+        self.setup_rules = rules;
+        self
+    }
+    pub fn players(&self) -> &[PlayerSetup] { &self.players }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.players.len() < MIN_PLAYERS {
+            Err(ConfigError::TooFewPlayers)
+        } else if self.players.len() > MAX_PLAYERS {
+            Err(ConfigError::TooManyPlayers)
+        } else if self.dealt_territories() < self.players.len() {
+            Err(ConfigError::NotEnoughTerritories)
+        } else if !self.dice_rules.is_valid() {
+            Err(ConfigError::InvalidDice)
+        } else if self.setup_rules.army.is_some_and(|army| (army as usize) < self.dealt_territories().div_ceil(self.players.len())) {
+            Err(ConfigError::ArmyTooSmall)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl GameConfig {
+    // Territories shared out between the players, after the neutral ones
+    // Troops the territory starts neutral with, from the config or else the map
+    pub(crate) fn neutral_troops(&self, territory: usize) -> Option<u32> {
+        self.neutral_territories.iter().find(|(t, _)| *t == territory).map(|(_, troops)| *troops)
+            .or_else(|| self.map.territories.get(territory).and_then(|t| t.neutral))
+    }
+
+    fn dealt_territories(&self) -> usize {
+        let open = (0..self.map.territories.len()).filter(|t| self.neutral_troops(*t).is_none()).count();
+        let neutral_share = if self.neutral_army.is_some() { open / (self.players.len() + 1) } else { 0 };
+        open - neutral_share
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self { GameConfig::new() }
+}
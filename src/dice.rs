@@ -0,0 +1,129 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{Game, TurnPhase};
+use crate::action::{Action, GameError};
+use crate::attack::Roll;
+use crate::odds;
+
+pub const MAX_DIE_FACES: u8 = 20;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DiceRules {
+    pub faces: u8,
+    // Most dice each side rolls at once. Players can choose to roll fewer, see choose_dice
+    pub attack_dice: u32,
+    pub defense_dice: u32,
+    pub ties_to_attacker: bool,
+    // Blitz attacks skip the dice. The winner is drawn from the true odds, and the troops it keeps
+    // are weighted towards the likeliest results so lucky and unlucky streaks are rarer
+    pub balanced_blitz: bool,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl DiceRules {
+    pub fn new() -> DiceRules {
+        DiceRules { faces: 6, attack_dice: 3, defense_dice: 2, ties_to_attacker: false, balanced_blitz: false }
+    }
+    pub fn is_valid(&self) -> bool {
+        (2..=MAX_DIE_FACES).contains(&self.faces)
+            && (1..=3).contains(&self.attack_dice)
+            && (1..=2).contains(&self.defense_dice)
+    }
+}
+
+impl Default for DiceRules {
+    fn default() -> Self { DiceRules::new() }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Game {
+    pub fn dice_rules(&self) -> DiceRules { self.config.dice_rules }
+    // Dice the on player rolls when attacking and when defending, up to what the rules allow. Only
+    // while placing or attacking, so the defense is a standing choice for the other players' turns
+    pub fn choose_dice(&mut self, attack: u32, defense: u32) -> Result<(), GameError> {
+        self.apply(Action::ChooseDice { attack, defense }).map(|_| ())
+    }
+    pub fn attack_dice_for(&self, player: usize) -> u32 {
+        self.players[player].attack_dice.unwrap_or(self.config.dice_rules.attack_dice)
+    }
+    pub fn defense_dice_for(&self, player: usize) -> u32 {
+        self.players[player].defense_dice.unwrap_or(self.config.dice_rules.defense_dice)
+    }
+}
+
+impl Game {
+    pub(crate) fn apply_choose_dice(&mut self, attack: u32, defense: u32) -> Result<(), GameError> {
+        if self.turn.phase != TurnPhase::Place { self.check_phase(TurnPhase::Attack)?; }
+        let rules = self.config.dice_rules;
+        if !(1..=rules.attack_dice).contains(&attack) || !(1..=rules.defense_dice).contains(&defense) {
+            return Err(GameError::InvalidDice);
+        }
+        let player = self.on_player_index();
+        self.players[player].attack_dice = Some(attack);
+        self.players[player].defense_dice = Some(defense);
+        Ok(())
+    }
+
+    // The rules narrowed down to the dice both players chose for a battle
+    pub(crate) fn battle_dice(&self, attacker: usize, defender: Option<usize>) -> DiceRules {
+        DiceRules {
+            attack_dice: self.attack_dice_for(attacker),
+            defense_dice: defender.map(|d| self.defense_dice_for(d)).unwrap_or(self.config.dice_rules.defense_dice),
+            ..self.config.dice_rules
+        }
+    }
+
+    pub(crate) fn roll_dice(&mut self, rules: &DiceRules, attack_dice: u32, defense_dice: u32) -> Roll {
+        let faces = rules.faces + 1;
+        let mut attacks: Vec<u8> = (0..attack_dice).map(|_| self.rng.gen_range(1, faces)).collect();
+        let mut defenses: Vec<u8> = (0..defense_dice).map(|_| self.rng.gen_range(1, faces)).collect();
+        attacks.sort_unstable_by(|a, b| b.cmp(a));
+        defenses.sort_unstable_by(|a, b| b.cmp(a));
+        let (mut attacker_losses, mut defender_losses) = (0, 0);
+        for (attack, defend) in attacks.iter().zip(defenses.iter()) {
+            if attack > defend || (attack == defend && rules.ties_to_attacker) {
+                defender_losses += 1
+            } else {
+                attacker_losses += 1
+            }
+        }
+        Roll {
+            attack_dice,
+            defense_dice,
+            attacker_losses,
+            defender_losses,
+            attackers_left: 0,
+            defenders_left: 0,
+            attack_faces: attacks,
+            defense_faces: defenses,
+        }
+    }
+
+    // Draws how the whole fight ends in one go: first who wins, from the true odds, then how many
+    // troops the winner keeps, from the balanced odds. Comes back as a single roll without any dice.
+    pub(crate) fn balanced_blitz(&mut self, rules: &DiceRules, attackers: u32, defenders: u32) -> Roll {
+        let odds = odds::odds_with(&DiceRules { balanced_blitz: true, ..*rules }, attackers, defenders);
+        let attacker_wins = self.rng.gen::<f64>() < odds.attacker_win();
+        let survivors = if attacker_wins { &odds.attacker_survivors } else { &odds.defender_survivors };
+        let mut pick = self.rng.gen::<f64>() * survivors[1..].iter().sum::<f64>();
+        let left = (1..survivors.len())
+            .find(|troops| { pick -= survivors[*troops]; pick < 0.0 })
+            .or_else(|| (1..survivors.len()).rev().find(|troops| survivors[*troops] > 0.0))
+            .unwrap_or(1) as u32;
+        let (attackers_left, defenders_left) = if attacker_wins { (left, 0) } else { (0, left) };
+        Roll {
+            attack_dice: 0,
+            defense_dice: 0,
+            attacker_losses: attackers - attackers_left,
+            defender_losses: defenders - defenders_left,
+            attackers_left,
+            defenders_left,
+            attack_faces: vec!(),
+            defense_faces: vec!(),
+        }
+    }
+}
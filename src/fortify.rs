@@ -0,0 +1,83 @@
+use std::collections::VecDeque;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::Game;
+
+// Where troops can be moved to when fortifying
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum FortifyReach {
+    // Neighboring territories only
+    #[default]
+    Adjacent = 0,
+    // Any territory reachable through the player's own territories, over borders troops can move across
+    Connected = 1,
+    // Any territory the player owns
+    Unlimited = 2,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FortifyRules {
+    // Fortifications allowed each turn, None for no limit. The turn ends once the last one is used.
+    pub per_turn: Option<u32>,
+    #[serde(default)]
+    pub reach: FortifyReach,
+}
+
+impl Default for FortifyRules {
+    fn default() -> Self {
+        FortifyRules { per_turn: Some(1), reach: FortifyReach::Adjacent }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Game {
+    pub fn fortify_reach(&self) -> FortifyReach { self.config.fortify_rules.reach }
+    pub fn fortifications_made(&self) -> u32 { self.turn.fortifications }
+    pub fn fortifications_left(&self) -> Option<u32> {
+        self.config.fortify_rules.per_turn.map(|limit| limit.saturating_sub(self.turn.fortifications))
+    }
+    // Troops that arrived by fortifying this turn and have to stay put until the next one
+    pub fn fortified_troops(&self, territory: usize) -> u32 {
+        self.turn.fortified.get(&territory).copied().unwrap_or(0)
+    }
+
+    // Territories the on player could move troops from `from` to, under the fortify reach rule
+    pub fn fortify_targets(&self, from: usize) -> Vec<u32> {
+        let owned = &self.on_player().territories;
+        if from >= self.map.territories.len() || !owned.contains(&(from as u32)) { return vec!(); }
+        match self.config.fortify_rules.reach {
+            FortifyReach::Adjacent => self.map.move_targets(from).into_iter()
+                .filter(|t| owned.contains(t))
+                .collect(),
+            FortifyReach::Connected => {
+                // Breadth first through owned territories
+                let mut seen = vec![false; self.map.territories.len()];
+                seen[from] = true;
+                let mut queue = VecDeque::from(vec!(from));
+                let mut reached = vec!();
+                while let Some(t) = queue.pop_front() {
+                    for n in self.map.move_targets(t) {
+                        let n = n as usize;
+                        if n < seen.len() && !seen[n] && owned.contains(&(n as u32)) {
+                            seen[n] = true;
+                            reached.push(n as u32);
+                            queue.push_back(n);
+                        }
+                    }
+                }
+                reached
+            },
+            FortifyReach::Unlimited => owned.iter().filter(|t| **t as usize != from).copied().collect(),
+        }
+    }
+}
+
+impl Game {
+    pub(crate) fn can_fortify(&self, from: usize, to: usize) -> bool {
+        self.fortify_targets(from).contains(&(to as u32))
+    }
+}
@@ -0,0 +1,119 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{Game, Map, Player, Turn};
+use crate::action::{Action, GameError, Outcome};
+use crate::attack::BattleLog;
+use crate::cards::Deck;
+use crate::player::Elimination;
+
+// Game state from just before an undoable action
+#[derive(Clone)]
+struct Snapshot {
+    map: Map,
+    players: Vec<Player>,
+    turn: Turn,
+    deck: Deck,
+    eliminations: Vec<Elimination>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    // Every action applied so far, in order
+    log: Vec<Action>,
+    // Every attack that rolled dice, pointing back into log
+    #[serde(default)]
+    battles: Vec<BattleLog>,
+    // Undo only reaches back to the last action that rolled dice, drew a card or passed play on
+    #[serde(skip)]
+    undo: Vec<Snapshot>,
+    #[serde(skip)]
+    redo: Vec<Action>,
+    // Set on throwaway copies of the game, e.g. bots looking ahead
+    #[serde(skip)]
+    disabled: bool,
+}
+
+impl History {
+    pub(crate) fn disabled() -> History {
+        History { disabled: true, ..History::default() }
+    }
+    pub fn actions(&self) -> &[Action] { &self.log }
+
+    // The history of a game rebuilt by playing `log`, keeping the battles it rolled
+    pub(crate) fn replayed(log: &[Action], battles: &[BattleLog]) -> History {
+        History {
+            log: log.to_vec(),
+            battles: battles.iter().filter(|b| b.action < log.len()).cloned().collect(),
+            ..History::default()
+        }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Game {
+    pub fn can_undo(&self) -> bool { !self.history.undo.is_empty() }
+    pub fn can_redo(&self) -> bool { !self.history.redo.is_empty() }
+    pub fn action_count(&self) -> usize { self.history.log.len() }
+    pub fn battle_count(&self) -> usize { self.history.battles.len() }
+    pub fn battle(&self, index: usize) -> Option<BattleLog> { self.history.battles.get(index).cloned() }
+    pub fn last_battle(&self) -> Option<BattleLog> { self.history.battles.last().cloned() }
+
+    pub fn undo(&mut self) -> Result<(), GameError> {
+        let snapshot = self.history.undo.pop().ok_or(GameError::NothingToUndo)?;
+        let action = self.history.log.pop().expect("every snapshot has a logged action");
+        self.history.redo.push(action);
+        self.map = snapshot.map;
+        self.players = snapshot.players;
+        self.turn = snapshot.turn;
+        self.deck = snapshot.deck;
+        self.eliminations = snapshot.eliminations;
+        Ok(())
+    }
+
+    pub fn redo(&mut self) -> Result<(), GameError> {
+        self.redo_action().map(|_| ())
+    }
+}
+
+impl Game {
+    pub fn history(&self) -> &[Action] { self.history.actions() }
+    pub fn battles(&self) -> &[BattleLog] { &self.history.battles }
+
+    pub fn redo_action(&mut self) -> Result<Outcome, GameError> {
+        let action = self.history.redo.pop().ok_or(GameError::NothingToRedo)?;
+        let remaining = std::mem::take(&mut self.history.redo);
+        let result = self.apply(action);
+        self.history.redo = remaining;
+        result
+    }
+
+    pub(crate) fn record<F>(&mut self, action: Action, apply: F) -> Result<Outcome, GameError>
+        where F: FnOnce(&mut Game, Action) -> Result<Outcome, GameError> {
+        if self.history.disabled { return apply(self, action); }
+        let snapshot = Snapshot {
+            map: self.map.clone(),
+            players: self.players.clone(),
+            turn: self.turn.clone(),
+            deck: self.deck.clone(),
+            eliminations: self.eliminations.clone(),
+        };
+        let rng_position = self.rng.word_pos();
+        let outcome = apply(self, action.clone())?;
+        // The next player can't take back the turn before theirs
+        let handed_over = self.turn.number != snapshot.turn.number || self.turn.player_index != snapshot.turn.player_index;
+        if self.rng.word_pos() == rng_position && !handed_over {
+            self.history.undo.push(snapshot);
+        } else {
+            self.history.undo.clear();
+            // Only attacks that rolled are kept, so undo never has to take a battle back out
+            if let Some(battle) = BattleLog::from_outcome(self, self.history.log.len(), &action, &outcome) {
+                self.history.battles.push(battle);
+            }
+        }
+        self.history.log.push(action);
+        self.history.redo.clear();
+        Ok(outcome)
+    }
+}
@@ -1,86 +1,194 @@
+#![allow(clippy::unused_unit)]
+
+#[cfg(feature = "wasm")]
 extern crate web_sys;
 extern crate rand;
+extern crate serde;
 
 mod utils;
+mod rng;
 
 pub mod player;
 pub mod map;
-
+pub mod cards;
+pub mod action;
+pub mod fortify;
+pub mod save;
+pub mod config;
+pub mod history;
+pub mod replay;
+pub mod ai;
+pub mod odds;
+pub mod attack;
+pub mod dice;
+pub mod setup;
+pub mod neutral;
+
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
+use std::collections::BTreeMap;
 use rand::Rng;
-use rand::prelude::*;
+use serde::{Deserialize, Serialize};
 
 pub use crate::map::*;
-pub use crate::map::territory::*;
-pub use crate::player::Player;
-
+pub use crate::player::{Player, Elimination};
+pub use crate::cards::*;
+pub use crate::action::*;
+pub use crate::fortify::*;
+pub use crate::save::*;
+pub use crate::config::*;
+pub use crate::history::History;
+pub use crate::replay::*;
+pub use crate::ai::{Agent, Bot};
+pub use crate::odds::{battle_odds, BattleOdds};
+pub use crate::attack::{AttackMode, BattleLog, Roll};
+pub use crate::dice::DiceRules;
+pub use crate::setup::{SetupRules, TerritoryDeal};
+pub use crate::neutral::NEUTRAL_COLOR;
+pub use crate::rng::GameRng;
+
+#[allow(unused_macros)]
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
 macro_rules! log {
     ( $( $t:tt )* ) => {
         web_sys::console::log_1(&format!( $( $t )* ).into());
     }
 }
 
+// Console logging is only available in the browser
+#[allow(unused_macros)]
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+macro_rules! log {
+    ( $( $t:tt )* ) => {
+        let _ = format_args!( $( $t )* );
+    }
+}
+
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
 // allocator.
 #[cfg(feature = "wee_alloc")]
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
-#[wasm_bindgen]
-#[derive(Clone, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, PartialOrd, PartialEq, Serialize, Deserialize)]
 pub enum TurnPhase {
     Place = 0,
     Attack = 1,
     Fortify = 2,
     PostAttackFortify = 3,
+    // Setup before the first turn, see setup
+    Claim = 4,
+    Deploy = 5,
 }
 
-#[wasm_bindgen]
-#[derive(Clone)]
+impl TurnPhase {
+    // Phase changes a player can ask for. The game itself also moves Attack <-> PostAttackFortify
+    // around a capture, and back to Place when an elimination forces a card trade mid attack.
+    pub fn can_transition_to(&self, next: &TurnPhase) -> bool {
+        self == next || matches!(
+            (self, next),
+            (TurnPhase::Place, TurnPhase::Attack) |
+            (TurnPhase::Place, TurnPhase::Fortify) |
+            (TurnPhase::Attack, TurnPhase::Fortify)
+        )
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Turn {
+    number: u32,
     player_index: u32,
     phase: TurnPhase,
     new_troops: u32,
+    captured_territory: bool,
+    // (attacker, captured) while waiting on troops to advance
+    pending_advance: Option<(usize, usize)>,
+    fortifications: u32,
+    // Troops moved into each territory by fortifying this turn, which can't move again
+    #[serde(default)]
+    fortified: BTreeMap<usize, u32>,
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 #[derive(Clone)]
+pub struct Reinforcements {
+    pub base: u32,
+    pub(crate) countries: Vec<u32>,
+    pub(crate) bonuses: Vec<u32>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Reinforcements {
+    // Indices into map countries, paired with bonuses()
+    pub fn countries(&self) -> Vec<u32> { self.countries.clone() }
+    pub fn bonuses(&self) -> Vec<u32> { self.bonuses.clone() }
+    pub fn total(&self) -> u32 { self.base + self.bonuses.iter().sum::<u32>() }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Game {
     map: Map,
     players: Vec<Player>,
     turn: Turn,
-    rng: StdRng
+    deck: Deck,
+    // The earliest version 1 saves have neither, those games were always two players on the built-in map
+    #[serde(default)]
+    config: GameConfig,
+    eliminations: Vec<Elimination>,
+    #[serde(default)]
+    history: History,
+    rng: GameRng
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl Game {
+    // Two players on the built-in map
     pub fn new() -> Game {
+        Game::with_config(GameConfig::default()).expect("default config is valid")
+    }
+
+    pub fn with_config(config: GameConfig) -> Result<Game, ConfigError> {
         utils::set_panic_hook();
+        config.validate()?;
 
-        let seed: u64 = 123;
-        let rng: StdRng = rand::SeedableRng::seed_from_u64(seed);
-        let map: Map = Map::new();
+        let rng = GameRng::seed_from_u64(config.seed);
+        let mut map: Map = config.map.clone();
+        if let Some(troops) = config.starting_troops {
+            map.territories.iter_mut().for_each(|t| t.troops = troops);
+        }
 
-        let players = vec!(
-            Player{ index: 0, color: 0xAA1111, territories: vec!() },
-            Player{ index: 0, color: 0x11AA11, territories: vec!() }
-        );
+        let players = config.players.iter().enumerate().map(|(i, setup)|
+            Player{ index: i as u32, color: setup.color, name: setup.name.clone(), territories: vec!(), cards: vec!(), bot: setup.bot, attack_dice: None, defense_dice: None, reserve: 0 }
+        ).collect();
 
-        let turn = Turn { player_index: 0, phase: TurnPhase::Place, new_troops: 0 };
+        let turn = Turn { number: 1, player_index: 0, phase: TurnPhase::Place, new_troops: 0, captured_territory: false, pending_advance: None, fortifications: 0, fortified: BTreeMap::new() };
+        let deck = Deck::new(map.territories.len(), config.card_rules.wild_cards);
 
         let mut game = Game {
             map,
             players,
             turn,
+            deck,
+            config,
+            eliminations: vec!(),
+            history: History::default(),
             rng
         };
-        game.assign_territories();
+        game.begin_setup();
         game.update_colors();
-        game.turn.new_troops = game.calc_troop_bonus() as u32;
-        game
+        Ok(game)
     }
 
+    pub fn player_count(&self) -> usize { self.players.len() }
+    pub fn player_name(&self, index: usize) -> String { self.players[index].name.clone() }
+    pub fn player_color(&self, index: usize) -> u32 { self.players[index].color }
+
     pub fn is_over(&self) -> bool {
+        // Nobody owns anything yet while drafting
+        if self.is_setup_phase() { return false; }
         let active_players: Vec<usize> = self.active_players();
         active_players.len() <= 1
     }
@@ -88,32 +196,35 @@ impl Game {
         self.players.iter().filter(|p| !p.is_eliminated()).map(|p| p.index as usize).collect()
     }
 
-    pub fn hit_troop_placement_limit(&self) -> bool { self.troops_available_for_placement() <= 0 }
+    pub fn hit_troop_placement_limit(&self) -> bool { self.troops_available_for_placement() == 0 }
 
-    pub fn map_click_action(&mut self, territory: usize) -> bool {
+    pub fn map_click_action(&mut self, territory: usize) -> Result<(), GameError> {
+        if self.is_over() { return Err(GameError::GameOver); }
+        if territory >= self.map.territories.len() { return Err(GameError::UnknownTerritory); }
+        let owned = self.on_player().territories.contains(&(territory as u32));
         match self.turn.phase {
             TurnPhase::Place => {
-                if self.on_player().territories.contains(&(territory as u32)) && !self.hit_troop_placement_limit() {
-                    self.map.territories[territory].state = TerritoryState::Selected;
-                    self.map.cache_troop_placement(territory); // todo -> pass in have value cached
-                    true
-                } else { false }
+                self.check_owned(territory)?;
+                if self.hit_troop_placement_limit() { return Err(GameError::NotEnoughTroops); }
+                self.map.territories[territory].state = TerritoryState::Selected;
+                self.map.cache_troop_placement(territory); // todo -> pass in have value cached
+                Ok(())
             },
             TurnPhase::Attack => {
-                if self.on_player().territories.contains(&(territory as u32)) {
+                if owned {
                     if !self.on_player().territories.iter().any(|t| self.map.territories[*t as usize].is_selected()) {
                         self.map.territories[territory].state = TerritoryState::Selected;
-                        self.map.territories[territory].neighbors.clone().iter().for_each(|t|
+                        self.map.attack_targets(territory).iter().for_each(|t|
                             if !self.on_player().territories.contains(t) {
                                 self.map.territories[*t as usize].state = TerritoryState::Highlighted;
                             }
                         );
-                        true
+                        Ok(())
                     } else if self.map.territories[territory].is_selected() {
                         self.unselect_all();
-                        true
-                    } else { false }
-                } else if territory != self.map.background_index && self.map.territories[territory].is_highlighted() {
+                        Ok(())
+                    } else { Err(GameError::OwnTerritory) }
+                } else if self.map.territories[territory].is_highlighted() {
                     let owned_territories = self.on_player().clone().territories;
                     self.map.territories.iter_mut().enumerate().for_each(|t|
                         if !(owned_territories.contains(&(t.0 as u32))) {
@@ -121,29 +232,30 @@ impl Game {
                         }
                     );
                     self.map.territories[territory].state = TerritoryState::Targeted;
-                    true
-                } else { false }
+                    Ok(())
+                } else if self.selected_territory_index().is_none() {
+                    Err(GameError::NoSelection)
+                } else { Err(GameError::NotAdjacent) }
             },
             TurnPhase::Fortify => {
-                if self.on_player().territories.contains(&(territory as u32)) {
-                    if !self.on_player().territories.iter().any(|t| self.map.territories[*t as usize].is_selected()) {
-                        self.map.territories[territory].state = TerritoryState::Selected;
-                        self.map.territories[territory].neighbors.clone().iter().for_each(|t|
-                            if self.on_player().territories.contains(t) {
-                                self.map.territories[*t as usize].state = TerritoryState::Highlighted;
-                            }
-                        );
-                        true
-                    } else if self.map.territories[territory].is_selected() {
-                        self.unselect_all();
-                        true
-                    } else if self.map.territories[territory].is_highlighted() {
-                        self.map.territories[territory].state = TerritoryState::Targeted;
-                        true
-                    } else { false }
-                } else { false }
+                if !owned { return Err(GameError::NotOwned); }
+                if !self.on_player().territories.iter().any(|t| self.map.territories[*t as usize].is_selected()) {
+                    self.map.territories[territory].state = TerritoryState::Selected;
+                    self.fortify_targets(territory).iter().for_each(|t|
+                        self.map.territories[*t as usize].state = TerritoryState::Highlighted
+                    );
+                    Ok(())
+                } else if self.map.territories[territory].is_selected() {
+                    self.unselect_all();
+                    Ok(())
+                } else if self.map.territories[territory].is_highlighted() {
+                    self.map.territories[territory].state = TerritoryState::Targeted;
+                    Ok(())
+                } else { Err(GameError::NotAdjacent) }
             },
-            TurnPhase::PostAttackFortify => false,
+            TurnPhase::PostAttackFortify => Err(GameError::AdvancePending),
+            TurnPhase::Claim => self.claim(territory),
+            TurnPhase::Deploy => self.deploy(territory, self.deploy_size()),
         }
     }
     pub fn get_troops_to_place(&self) -> usize { self.map.troops_to_place }
@@ -154,39 +266,59 @@ impl Game {
     }
 
     pub fn new_troops(&self) -> u32 { self.turn.new_troops }
+    pub fn reinforcements(&self) -> Reinforcements {
+        self.reinforcements_for(self.on_player())
+    }
     pub fn troops_available_for_placement(&self) -> u32 {
         let uncommitted: u32 = self.turn.new_troops;
         let cached: usize = self.map.troop_placement_cache.values().sum();
-        uncommitted - (cached as u32)
+        uncommitted.saturating_sub(cached as u32)
     }
 
-    pub fn commit_placement_cache(&mut self) -> () {
+    pub fn commit_placement_cache(&mut self) -> Result<(), GameError> {
         let troops_placed: usize = self.map.troop_placement_cache.values().sum();
-        let updated_troops_available = self.turn.new_troops - troops_placed as u32;
-        self.map.troop_placement_cache.clone().iter().for_each(|(territory, troops)| self.add_troops(territory, troops));
+        if troops_placed as u32 > self.turn.new_troops { return Err(GameError::NotEnoughTroops); }
+        let mut placements: Vec<(usize, usize)> = self.map.troop_placement_cache.clone().into_iter().collect();
+        placements.sort_unstable();
+        // Nothing is placed unless all of it can be
+        for (territory, troops) in &placements { self.check_place(*territory, *troops as u32)?; }
+        for (territory, troops) in placements {
+            self.apply(Action::PlaceTroops { territory, troops: troops as u32 })?;
+            self.map.troop_placement_cache.remove(&territory);
+        }
         self.unselect_all();
-        self.map.troop_placement_cache.clear();
-        self.turn.new_troops = updated_troops_available;
+        Ok(())
     }
 
-    // TODO: These need checks that each is allowed
-    //       for now just move to segment whenever button is pressed
-    pub fn place_phase(&mut self) -> () { self.turn.phase = TurnPhase::Place; }
-    pub fn attack_phase(&mut self) -> () { self.turn.phase = TurnPhase::Attack; }
-    pub fn fortify_phase(&mut self) -> () { self.turn.phase = TurnPhase::Fortify; }
+    pub fn place_phase(&mut self) -> Result<(), GameError> { self.set_phase(TurnPhase::Place) }
+    pub fn attack_phase(&mut self) -> Result<(), GameError> { self.set_phase(TurnPhase::Attack) }
+    pub fn fortify_phase(&mut self) -> Result<(), GameError> { self.set_phase(TurnPhase::Fortify) }
+
+    pub fn hand(&self) -> Vec<Card> { self.on_player().cards.clone() }
+    pub fn hand_size(&self) -> usize { self.on_player().cards.len() }
+    pub fn has_card_set(&self) -> bool { find_set(&self.on_player().cards).is_some() }
+    pub fn must_trade_cards(&self) -> bool {
+        self.hand_size() >= self.config.card_rules.forced_trade_at && self.has_card_set()
+    }
+    pub fn cards_traded(&self) -> u32 { self.deck.trades() }
+    pub fn eliminations(&self) -> Vec<Elimination> { self.eliminations.clone() }
 
     pub fn is_place_phase(&self) -> bool { self.turn.phase == TurnPhase::Place }
     pub fn is_attack_phase(&self) -> bool { self.turn.phase == TurnPhase::Attack }
     pub fn is_fortify_phase(&self) -> bool {
-        vec!(TurnPhase::Fortify, TurnPhase::PostAttackFortify).contains(&self.turn.phase)
+        [TurnPhase::Fortify, TurnPhase::PostAttackFortify].contains(&self.turn.phase)
     }
 
     pub fn target_selected(&self) -> bool {
-        self.map.territories.iter().find(|t| t.is_targeted()).is_some()
+        self.map.territories.iter().any(|t| t.is_targeted())
+    }
+
+    pub fn territory_owner(&self, territory: usize) -> Option<u32> {
+        self.players.iter().find(|p| p.territories.contains(&(territory as u32))).map(|p| p.index)
     }
 
     pub fn on_player_index(&self) -> usize {
-        self.turn.player_index.clone() as usize
+        self.turn.player_index as usize
     }
 
     pub fn turn_phase(&self) -> TurnPhase {
@@ -195,16 +327,19 @@ impl Game {
 
     pub fn update_colors(&mut self) -> () {
         self.map.set_all_territory_colors(&self.players);
+        self.color_neutrals();
     }
     pub fn assign_territories(&mut self) -> () {
         let player_count = self.players.len();
-        let mut unassigned: Vec<usize> = (0..self.map.territories.len()).collect();
+        let mut unassigned: Vec<usize> = (0..self.map.territories.len())
+            .filter(|t| self.map.territories[*t].neutral.is_none())
+            .collect();
         let mut counter: usize = 0;
-        while unassigned.len() > 0 {
+        while !unassigned.is_empty() {
             let next_index = self.rng.gen_range(0,unassigned.len());
             let next_territory = unassigned.remove(next_index);
             self.players[counter % player_count].territories.push(next_territory as u32);
-            counter = counter + 1;
+            counter += 1;
         };
     }
     pub fn assign_territory(&mut self, territory: u32, player_index: u32) -> () {
@@ -219,92 +354,53 @@ impl Game {
 
     pub fn get_map(&self) -> Map { self.map.clone() }
 
-    pub fn init_turn(&mut self) -> () {
-        self.turn.player_index = (self.turn.player_index + 1) % self.players.len() as u32;
-        let troops = self.calc_troop_bonus() as u32;
-        self.turn.new_troops = troops;
-        self.turn.phase = TurnPhase::Place;
-    }
+    pub fn init_turn(&mut self) -> Result<(), GameError> { self.end_turn() }
     pub fn troops_staged_for_placement(&self) -> usize {
         self.map.troop_placement_cache.values().sum()
     }
+    // Troops on the selected territory, less any that already moved there this turn when fortifying
     pub fn troops_available_for_movement(&self) -> usize {
-        let total_troops = self.map.territories.iter().find(|t| t.is_selected()).map(|t| t.troops);
-        *(total_troops.map(|t| t as usize).get_or_insert(0))
+        match self.selected_territory_index() {
+            Some(t) if self.turn.phase == TurnPhase::Fortify => (self.map.territories[t].troops - self.fortified_troops(t)) as usize,
+            Some(t) => self.map.territories[t].troops as usize,
+            None => 0,
+        }
     }
 
-    pub fn attack_tail(&mut self) -> () {
-        self.attack_all();
+    pub fn attack_tail(&mut self) -> Result<(), GameError> {
+        self.attack_all()?;
         if self.turn_phase() == TurnPhase::PostAttackFortify {
-            self.fortify_all();
-            self.turn.phase = TurnPhase::Attack;
+            self.fortify_all()?;
         }
+        Ok(())
     }
 
-    // Todo: should stop at 3 left to attack
-    pub fn attack_all(&mut self) -> () {
-        match self.selected_territory_index() {
-            Some(attacker) => {
-                let attack_troops = self.map.territories[attacker].troops - 1;
-                self.attack_with(attack_troops);
-            }
-            _ => ()
-        }
+    // Fights to the last troop, see attack_until_troops_left for stopping earlier
+    pub fn attack_all(&mut self) -> Result<(), GameError> {
+        let attacker = self.selected_territory_index().ok_or(GameError::NoSelection)?;
+        let attack_troops = self.map.territories[attacker].troops.saturating_sub(1);
+        self.attack_with(attack_troops)
     }
 
-    pub fn attack_with(&mut self, troops: u32) -> () {
+    pub fn attack_with(&mut self, troops: u32) -> Result<(), GameError> {
         match (self.selected_territory_index(), self.targeted_territory_index()) {
-            (Some(attacker), Some(defender)) => {
-                let attack_reserves = self.map.territories[attacker].troops - troops;
-                let defend_with = self.map.territories[defender].troops;
-                let losses = self.roll_all(troops, defend_with);
-                let remaining_attackers = troops - losses.attack_dice;
-                let remaining_defenders = defend_with - losses.defend_dice;
-                if remaining_defenders == 0 { // Territory captured
-                    let remaining_troops = if remaining_attackers > 3 { remaining_attackers - 3 } else { 1 } + attack_reserves;
-                    self.map.territories[attacker].troops = remaining_troops;
-                    self.map.territories[defender].troops = std::cmp::min(remaining_attackers, 3);
-                    let player_idx = self.on_player_index().clone();
-                    self.players.iter_mut().find(|p| p.territories.contains(&(defender as u32)))
-                        .map(|p| p.territories.retain(|i| i != &(defender as u32)));
-                    self.players[player_idx].capture_territory(defender as u32);
-                    self.update_colors();
-                    if remaining_troops <= 1 { self.unselect_all(); } else { self.turn.phase = TurnPhase::PostAttackFortify }
-                } else {
-                    self.map.territories[attacker].troops = attack_reserves + remaining_attackers;
-                    self.map.territories[defender].troops = remaining_defenders;
-                }
-            },
-            _ => ()
+            (Some(from), Some(to)) => self.attack(from, to, troops).map(|_| ()),
+            _ => Err(GameError::NoSelection),
         }
     }
 
-    pub fn fortify_all(&mut self) -> () {
-        let selected_idx = self.selected_territory_index();
-        let targeted_idx = self.targeted_territory_index();
-        match (selected_idx, targeted_idx) {
-            (Some(source), Some(destination)) => {
-                let troops = self.map.territories[source].troops.clone() - 1;
-                self.map.territories[source].sub_troops(troops as u32);
-                self.map.territories[destination].add_troops(troops as u32);
-                self.unselect_all();
-            }
-            _ => ()
-        }
+    pub fn fortify_all(&mut self) -> Result<(), GameError> {
+        let source = self.selected_territory_index().ok_or(GameError::NoSelection)?;
+        let troops = self.map.territories[source].troops.saturating_sub(1);
+        self.fortify_troops(troops as usize)
     }
-    pub fn fortify_troops(&mut self, troops: usize) -> () {
-        let selected_idx = self.selected_territory_index();
-        let targeted_idx = self.targeted_territory_index();
-        match (selected_idx, targeted_idx) {
-            (Some(source), Some(destination)) => {
-                self.map.territories[source].sub_troops(troops as u32);
-                self.map.territories[destination].add_troops(troops as u32);
-                if self.turn.phase == TurnPhase::PostAttackFortify {
-                    self.turn.phase = TurnPhase::Attack;
-                }
-                self.unselect_all();
-            }
-            _ => ()
+    pub fn fortify_troops(&mut self, troops: usize) -> Result<(), GameError> {
+        if self.turn.phase == TurnPhase::PostAttackFortify {
+            return self.advance(troops as u32);
+        }
+        match (self.selected_territory_index(), self.targeted_territory_index()) {
+            (Some(from), Some(to)) => self.fortify(from, to, troops as u32),
+            _ => Err(GameError::NoSelection),
         }
     }
     pub fn unselect_all(&mut self) -> () {
@@ -312,31 +408,122 @@ impl Game {
     }
 }
 
+impl Default for Game {
+    fn default() -> Self { Game::new() }
+}
+
 impl Game {
+    pub(crate) fn start_next_turn(&mut self) -> () {
+        if self.turn.captured_territory {
+            let player_idx = self.on_player_index();
+            self.award_card(player_idx);
+        }
+        self.turn.captured_territory = false;
+        self.turn.pending_advance = None;
+        self.turn.fortifications = 0;
+        self.turn.fortified.clear();
+        self.turn.number += 1;
+        let player_count = self.players.len() as u32;
+        self.turn.player_index = (self.turn.player_index + 1) % player_count;
+        while self.on_player().is_eliminated() && !self.is_over() {
+            self.turn.player_index = (self.turn.player_index + 1) % player_count;
+        }
+        let troops = self.calc_troop_bonus() as u32;
+        self.turn.new_troops = troops;
+        self.turn.phase = TurnPhase::Place;
+    }
     pub fn on_player(&self) -> &Player {
         &(self.players[self.on_player_index()])
     }
     pub fn add_troops(&mut self, target: &usize, troops: &usize) -> () {
-        let current_troops = &self.map.territories[*target].troops;
-        let new_troops = *current_troops + (*troops as u32);
+        let current_troops = self.map.territories[*target].troops;
+        let new_troops = current_troops + (*troops as u32);
         self.map.territories[*target].troops = new_troops;
     }
     pub fn sub_troops(&mut self, target: &usize, troops: &usize) -> () {
-        let current_troops = &self.map.territories[*target].troops;
-        let new_troops = *current_troops - (*troops as u32); // TODO: Handle negatives
+        let current_troops = self.map.territories[*target].troops;
+        let new_troops = current_troops.saturating_sub(*troops as u32);
         self.map.territories[*target].troops = new_troops;
     }
     pub fn set_troops(&mut self, target: &usize, troops: &usize) -> () {
        self.map.territories[*target].troops = *troops as u32;
     }
+    pub fn config(&self) -> &GameConfig { &self.config }
+    pub fn card_rules(&self) -> &CardRules { &self.config.card_rules }
+    pub fn set_card_rules(&mut self, rules: CardRules) -> () {
+        self.deck = Deck::new(self.map.territories.len(), rules.wild_cards);
+        self.config.card_rules = rules;
+    }
+    pub fn turn_number(&self) -> u32 { self.turn.number }
+    pub fn fortify_rules(&self) -> &FortifyRules { &self.config.fortify_rules }
+    pub fn set_fortify_rules(&mut self, rules: FortifyRules) -> () { self.config.fortify_rules = rules; }
+
+    // Removes the set at the given hand positions from the on player, returning the troops it was worth
+    pub(crate) fn trade_set(&mut self, indices: &[usize]) -> u32 {
+        let player_idx = self.on_player_index();
+        let set: Vec<Card> = indices.iter().map(|i| self.players[player_idx].cards[*i]).collect();
+        let mut sorted = indices.to_vec();
+        sorted.sort_unstable();
+        sorted.iter().rev().for_each(|i| { self.players[player_idx].cards.remove(*i); });
+        let troops = self.config.card_rules.trade_value(self.deck.trades, &set);
+        self.turn.new_troops += troops;
+        self.deck.trades += 1;
+        let owned_territory = set.iter().filter_map(|c| c.territory)
+            .find(|t| self.players[player_idx].territories.contains(t));
+        if let Some(territory) = owned_territory {
+            self.map.territories[territory as usize].add_troops(self.config.card_rules.territory_bonus);
+        }
+        self.deck.discard(&set);
+        troops
+    }
+
+    // The eliminator takes the eliminated player's hand and must immediately trade down below the limit
+    fn eliminate_player(&mut self, eliminated: usize, eliminator: usize) -> () {
+        self.eliminations.push(Elimination {
+            player: self.players[eliminated].index,
+            eliminated_by: self.players[eliminator].index,
+            turn: self.turn.number,
+        });
+        let mut captured_cards = std::mem::take(&mut self.players[eliminated].cards);
+        self.players[eliminator].cards.append(&mut captured_cards);
+        while self.must_trade_cards() {
+            if let Some(set) = find_set(&self.on_player().cards) {
+                self.trade_set(&set);
+            }
+        }
+    }
+
+    // Troops gained from trading cards mid attack have to be placed before attacking again
+    pub(crate) fn resume_attack(&mut self) -> () {
+        self.turn.phase = if self.troops_available_for_placement() > 0 { TurnPhase::Place } else { TurnPhase::Attack };
+    }
+
+    pub fn award_card(&mut self, player_index: usize) -> () {
+        if let Some(card) = self.deck.draw(&mut self.rng) {
+            self.players[player_index].cards.push(card);
+        }
+    }
     pub fn calc_troop_bonus(&self) -> usize {
-        5 as usize
+        self.reinforcements().total() as usize
+    }
+    // Classic rules: territories / 3 (minimum 3), plus the bonus for each country held outright
+    pub fn reinforcements_for(&self, player: &Player) -> Reinforcements {
+        let base = std::cmp::max(player.territories.len() as u32 / 3, 3);
+        let held: Vec<(u32, u32)> = self.map.countries.iter().enumerate()
+            .filter(|(_, c)| c.is_held_by(player))
+            .map(|(i, c)| (i as u32, c.bonus))
+            .collect();
+        Reinforcements {
+            base,
+            countries: held.iter().map(|c| c.0).collect(),
+            bonuses: held.iter().map(|c| c.1).collect(),
+        }
     }
     pub fn selected_territory_with_index(&self) -> Option<(usize, &Territory)> {
-        self.map.territories.iter().enumerate().find(|t| (*t).1.is_selected())
+        self.map.territories.iter().enumerate().find(|t| t.1.is_selected())
     }
     pub fn targeted_territory_with_index(&self) -> Option<(usize, &Territory)> {
-        self.map.territories.iter().enumerate().find(|t| (*t).1.is_targeted())
+        self.map.territories.iter().enumerate().find(|t| t.1.is_targeted())
     }
     pub fn selected_territory_index(&self) -> Option<usize> {
         self.selected_territory_with_index().map(|t| t.0)
@@ -345,40 +532,70 @@ impl Game {
         self.targeted_territory_with_index().map(|t| t.0)
     }
 
-    // Returns how many troops lost: (attack, defense)
-    fn roll_all(&mut self, attack_with: u32, defend_with: u32) -> AttackResults {
-        let mut attack_losses: u32 = 0;
-        let mut defend_losses: u32 = 0;
-        while attack_with > attack_losses && defend_with > defend_losses {
-            let attackers = std::cmp::min(attack_with - attack_losses, 3);
-            let defenders = std::cmp::min(defend_with - defend_losses, 2);
-            let losses = self.roll_dice(attackers, defenders);
-            attack_losses += losses.attack_dice;
-            defend_losses += losses.defend_dice;
-        }
-        AttackResults { attack_dice: attack_losses, defend_dice: defend_losses }
-    }
-
-    fn roll_dice(&mut self, attack_dice: u32, defense_dice: u32) -> AttackResults {
-        let mut attacks: Vec<u8> = vec![0; attack_dice as usize].iter_mut().map(|_| self.rng.gen_range(1,7)).collect();
-        let mut defenses: Vec<u8> = vec![0; defense_dice as usize].iter_mut().map(|_| self.rng.gen_range(1,7)).collect();
-        attacks.sort();
-        defenses.sort();
-        let mut results = AttackResults { attack_dice: 0, defend_dice: 0 };
-        while !attacks.is_empty() && !defenses.is_empty() {
-            match (attacks.pop(), defenses.pop()) {
-                (Some(attack), Some(defend)) if attack > defend => results.defend_dice += 1,
-                (Some(_), Some(_)) => results.attack_dice += 1,
-                _ => ()
+    // Fights until either side runs out or the mode stops it, moving survivors into the territory if it falls
+    pub(crate) fn resolve_attack(&mut self, attacker: usize, defender: usize, troops: u32, mode: AttackMode) -> Outcome {
+        let attack_reserves = self.map.territories[attacker].troops - troops;
+        let defend_with = self.map.territories[defender].troops;
+        let defender_idx = self.players.iter().position(|p| p.territories.contains(&(defender as u32)));
+        let dice = self.battle_dice(self.on_player_index(), defender_idx);
+        let rolls = self.roll_all(&dice, troops, defend_with, mode);
+        let attacker_losses: u32 = rolls.iter().map(|r| r.attacker_losses).sum();
+        let defender_losses: u32 = rolls.iter().map(|r| r.defender_losses).sum();
+        let remaining_attackers = troops - attacker_losses;
+        let remaining_defenders = defend_with - defender_losses;
+        let captured = remaining_defenders == 0;
+        if captured {
+            let moved_in = std::cmp::min(remaining_attackers, 3);
+            let remaining_troops = attack_reserves + remaining_attackers - moved_in;
+            self.map.territories[attacker].troops = remaining_troops;
+            self.map.territories[defender].troops = moved_in;
+            let player_idx = self.on_player_index();
+            if let Some(d) = defender_idx {
+                self.players[d].territories.retain(|i| i != &(defender as u32));
+            }
+            self.players[player_idx].capture_territory(defender as u32);
+            self.turn.captured_territory = true;
+            self.update_colors();
+            if let Some(d) = defender_idx.filter(|d| self.players[*d].is_eliminated()) {
+                self.eliminate_player(d, player_idx);
+            }
+            if remaining_troops <= 1 || self.is_over() {
+                self.unselect_all();
+                self.resume_attack();
+            } else {
+                self.turn.pending_advance = Some((attacker, defender));
+                self.turn.phase = TurnPhase::PostAttackFortify;
             }
+        } else {
+            self.map.territories[attacker].troops = attack_reserves + remaining_attackers;
+            self.map.territories[defender].troops = remaining_defenders;
         }
-        results
+        Outcome::Battle { attacker_losses, defender_losses, captured, rolls }
     }
 
-}
+    fn roll_all(&mut self, dice: &DiceRules, attack_with: u32, defend_with: u32, mode: AttackMode) -> Vec<Roll> {
+        if mode == AttackMode::Blitz && dice.balanced_blitz && attack_with > 0 && defend_with > 0 {
+            return vec!(self.balanced_blitz(dice, attack_with, defend_with));
+        }
+        let mut rolls: Vec<Roll> = vec!();
+        let (mut attackers, mut defenders) = (attack_with, defend_with);
+        while attackers > 0 && defenders > 0 {
+            let stop = match mode {
+                AttackMode::SingleRoll => !rolls.is_empty(),
+                AttackMode::UntilOddsBelow(percent) =>
+                    odds::odds_with(dice, attackers, defenders).attacker_win() * 100.0 < percent as f64,
+                AttackMode::UntilTroopsLeft(_) | AttackMode::Blitz => false,
+            };
+            if stop { break; }
+            let mut roll = self.roll_dice(dice, std::cmp::min(attackers, dice.attack_dice), std::cmp::min(defenders, dice.defense_dice));
+            attackers -= roll.attacker_losses;
+            defenders -= roll.defender_losses;
+            roll.attackers_left = attackers;
+            roll.defenders_left = defenders;
+            rolls.push(roll);
+        }
+        rolls
+    }
 
-struct AttackResults {
-    attack_dice: u32,
-    defend_dice: u32
 }
 
@@ -1,37 +1,62 @@
 pub mod country;
 pub mod territory;
+pub mod border;
+pub mod format;
+pub mod validate;
+pub mod wargear;
+pub mod generate;
 
 use std::collections::HashMap;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
 
 pub use crate::player::Player;
 pub use crate::map::territory::*;
 pub use crate::map::country::*;
+pub use crate::map::border::{Border, BorderKind};
+pub use crate::map::format::MapError;
+pub use crate::map::validate::MapProblem;
+pub use crate::map::wargear::WarGearImport;
+pub use crate::map::generate::MapGenerator;
 
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
 macro_rules! log {
     ( $( $t:tt )* ) => {
         web_sys::console::log_1(&format!( $( $t )* ).into());
     }
 }
 
-#[wasm_bindgen]
-#[derive(Clone)]
+// Console logging is only available in the browser
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+macro_rules! log {
+    ( $( $t:tt )* ) => {
+        let _ = format_args!( $( $t )* );
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Map {
+    #[serde(default)]
+    pub(crate) name: String,
     pub width: u32,
     pub height: u32,
     pub(crate) territories: Vec<Territory>,
+    pub(crate) countries: Vec<Country>,
     pub background_color: usize,
     pub background_index: usize,
     pub(crate) troops_to_place: usize,
     pub(crate) troop_placement_cache: HashMap<usize, usize>
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl Map {
     pub fn new() -> Map {
-        // Todo: This should fetch from DB
+        // The built-in map, see from_json for loading others
         Map::build_map()
     }
+    pub fn name(&self) -> String { self.name.clone() }
     pub fn width(&self) -> u32 { self.width }
     pub fn height(&self) -> u32 { self.height }
     pub fn background_color(&self) -> usize { self.background_color }
@@ -45,6 +70,26 @@ impl Map {
     pub fn vertices_for(&self, index: usize) -> Vec<u32> {
         self.territories[index].vertices.clone()
     }
+    pub fn territory_name(&self, index: usize) -> String {
+        self.territories[index].name.clone()
+    }
+    // Every territory across a border, whatever the border allows
+    pub fn neighbors(&self, index: usize) -> Vec<u32> {
+        self.territories[index].borders.iter().map(|b| b.to).collect()
+    }
+    pub fn borders(&self, index: usize) -> Vec<Border> {
+        self.territories[index].borders.clone()
+    }
+    pub fn border_kind(&self, from: usize, to: usize) -> Option<BorderKind> {
+        self.territories[from].borders.iter().find(|b| b.to as usize == to).map(|b| b.kind)
+    }
+    // Territories the territory can attack, or move troops to, over its borders
+    pub fn attack_targets(&self, index: usize) -> Vec<u32> {
+        self.territories[index].borders.iter().filter(|b| b.kind.attacks()).map(|b| b.to).collect()
+    }
+    pub fn move_targets(&self, index: usize) -> Vec<u32> {
+        self.territories[index].borders.iter().filter(|b| b.kind.moves()).map(|b| b.to).collect()
+    }
     pub fn centers(&self) -> Vec<u32> {
         self.territories.iter().map(|x| x.center).collect()
     }
@@ -57,7 +102,7 @@ impl Map {
     }
 
     pub fn color_for(&self, index: usize) -> u32 {
-        self.territories[index].color.clone()
+        self.territories[index].color
     }
 
     pub fn territory_with_color(&self, color: usize) -> usize {
@@ -66,6 +111,13 @@ impl Map {
         ).map(|x| x.0).get_or_insert(self.background_index)
     }
 
+    pub fn country_count(&self) -> usize { self.countries.len() }
+    pub fn country_name(&self, index: usize) -> String { self.countries[index].name.clone() }
+    pub fn country_bonus(&self, index: usize) -> u32 { self.countries[index].bonus }
+    pub fn country_territories(&self, index: usize) -> Vec<u32> {
+        self.countries[index].territories.clone()
+    }
+
     pub fn troops(&self) -> Vec<u32> {
         self.territories.iter().enumerate().map(|x| self.troops_to_display(x)).collect()
     }
@@ -107,9 +159,11 @@ impl Map {
     pub fn unselect(&mut self, index: usize) -> () {
         self.territories[index].state = TerritoryState::Dormant
     }
-    pub fn can_attack(self, attacker: usize, target: usize) -> bool {
-        let x = target as u32;
-        self.territories[attacker].neighbors.contains(&x)
+    pub fn can_attack(&self, attacker: usize, target: usize) -> bool {
+        self.border_kind(attacker, target).is_some_and(|k| k.attacks())
+    }
+    pub fn can_move(&self, from: usize, to: usize) -> bool {
+        self.border_kind(from, to).is_some_and(|k| k.moves())
     }
 
     pub fn cache_troop_placement(&mut self, index: usize) -> usize {
@@ -127,22 +181,26 @@ impl Map {
 
 }
 
+impl Default for Map {
+    fn default() -> Self { Map::new() }
+}
+
 impl Map {
     // Index of map.territory + 1, to account for outside = 0
     pub fn match_color_with_index(index: &usize, territory_blue: isize, click_blue: isize) -> bool {
         let sign = (128 - territory_blue).signum();
         let i = *index as isize;
-        territory_blue + (sign * i as isize) == click_blue
+        territory_blue + (sign * i) == click_blue
     }
     pub fn troop_placement_cache(&mut self, index: &usize) -> usize {
         let troops: usize = self.troops_to_place;
         log!("[rust] placing {} troops", troops);
-        let new_troops: usize = self.troop_placement_cache.get(index).map(|x| x + troops).get_or_insert(troops).clone();
+        let new_troops: usize = *self.troop_placement_cache.get(index).map(|x| x + troops).get_or_insert(troops);
         self.troop_placement_cache.insert(*index, new_troops);
         self.territories[*index].state = TerritoryState::Selected;
         new_troops
     }
-    pub fn set_all_territory_colors(&mut self, players: &Vec<Player>) -> () {
+    pub fn set_all_territory_colors(&mut self, players: &[Player]) -> () {
         for player in players {
             for territory in &player.territories {
                 self.set_color_for(*territory as usize, player.color as usize);
@@ -150,8 +208,8 @@ impl Map {
         }
     }
     fn troops_to_display(&self, indexed_territory: (usize, &Territory)) -> u32 {
-        let cached = self.troop_placement_cache.get(&indexed_territory.0).get_or_insert(&0).clone();
-        indexed_territory.1.troops as u32 + (cached as u32)
+        let cached = **self.troop_placement_cache.get(&indexed_territory.0).get_or_insert(&0);
+        indexed_territory.1.troops + (cached as u32)
     }
 }
 
@@ -163,57 +221,73 @@ impl Map {
 impl Map {
     pub fn build_map() -> Map {
         let c1 = Country {
+            name: String::from("North"),
             territories: vec![0,1],
-            border_color: 0xFF0000
+            border_color: 0xFF0000,
+            bonus: 2,
         };
         let c2 = Country {
+            name: String::from("South"),
             territories: vec![2,3],
-            border_color: 0x00FFCC
+            border_color: 0x00FFCC,
+            bonus: 3,
         };
         let t1 = Territory {
+            name: String::new(),
             vertices: vec![17,23,39,85,66,17],
             center: 52,
-            color: c1.border_color.clone(),
+            color: c1.border_color,
             troops: 167,
             state: TerritoryState::Dormant,
-            neighbors: vec!(1,2,3),
+            borders: Border::normal(&[1,2,3]),
+            neutral: None,
         };
         let t2 = Territory {
+            name: String::new(),
             vertices: vec![23,39,56,107,94,45,23],
             center: 75,
-            color: c1.border_color.clone(),
+            color: c1.border_color,
             troops: 289,
             state: TerritoryState::Dormant,
-            neighbors: vec!(0,2,4)
+            borders: Border::normal(&[0,2,4]),
+            neutral: None,
         };
         let t3 = Territory {
+            name: String::new(),
             vertices: vec![85,39,56,107,169,216,85],
             center: 104,
-            color: c2.border_color.clone(),
+            color: c2.border_color,
             troops: 3,
             state: TerritoryState::Dormant,
-            neighbors: vec!(0,1,3,4)
+            borders: Border::normal(&[0,1,3,4]),
+            neutral: None,
         };
         let t4 = Territory {
+            name: String::new(),
             vertices: vec![66,85,216,246,128,66],
             center: 148,
-            color: c2.border_color.clone(),
+            color: c2.border_color,
             troops: 4,
             state: TerritoryState::Dormant,
-            neighbors: vec!(0,2)
+            borders: Border::normal(&[0,2]),
+            neutral: None,
         };
         let t5 = Territory {
+            name: String::new(),
             vertices: vec![94,220,216,169,107,94],
             center: 156,
-            color: c2.border_color.clone(),
+            color: c2.border_color,
             troops: 5,
             state: TerritoryState::Dormant,
-            neighbors: vec!(1,2)
+            borders: Border::normal(&[1,2]),
+            neutral: None,
         };
         Map {
+            name: String::from("Tiny"),
             width: 16,
             height: 16,
             territories: vec![t1,t2,t3,t4,t5],
+            countries: vec![c1,c2],
             background_color: 0,
             background_index: 16777215,
             troops_to_place: 1,
@@ -0,0 +1,57 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize};
+
+// What a border lets the territory it belongs to do. Each side of a border has its own entry, so a
+// one way border is a Normal (or AttackOnly) border on one side and a DefendOnly one on the other.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum BorderKind {
+    // Attack and move troops across
+    #[default]
+    Normal = 0,
+    // Attack across but not move troops, like artillery
+    AttackOnly = 1,
+    // Move troops across but not attack
+    FortifyOnly = 2,
+    // Only be attacked across, by the other side's border
+    DefendOnly = 3,
+}
+
+impl BorderKind {
+    pub fn attacks(&self) -> bool { matches!(self, BorderKind::Normal | BorderKind::AttackOnly) }
+    pub fn moves(&self) -> bool { matches!(self, BorderKind::Normal | BorderKind::FortifyOnly) }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Border {
+    pub to: u32,
+    pub kind: BorderKind,
+}
+
+impl Border {
+    pub fn new(to: u32, kind: BorderKind) -> Border { Border { to, kind } }
+    // Normal borders to each of the territories
+    pub fn normal(to: &[u32]) -> Vec<Border> {
+        to.iter().map(|t| Border::new(*t, BorderKind::Normal)).collect()
+    }
+}
+
+// A neighbor index from a save made before typed borders, or a border
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedBorder {
+    Neighbor(u32),
+    Border(Border),
+}
+
+// Binary saves only ever hold borders, they are positional and can't tell the two apart
+pub(crate) fn saved_borders<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Border>, D::Error> {
+    if !deserializer.is_human_readable() { return Vec::<Border>::deserialize(deserializer); }
+    Ok(Vec::<SavedBorder>::deserialize(deserializer)?.into_iter().map(|b| match b {
+        SavedBorder::Neighbor(to) => Border::new(to, BorderKind::Normal),
+        SavedBorder::Border(border) => border,
+    }).collect())
+}
@@ -1,5 +1,17 @@
-#[derive(Clone)]
+use serde::{Deserialize, Serialize};
+
+use crate::player::Player;
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Country {
+    pub name: String,
     pub territories: Vec<u32>,
-    pub border_color: u32
+    pub border_color: u32,
+    pub bonus: u32,
+}
+
+impl Country {
+    pub fn is_held_by(&self, player: &Player) -> bool {
+        !self.territories.is_empty() && self.territories.iter().all(|t| player.territories.contains(t))
+    }
 }
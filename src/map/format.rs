@@ -0,0 +1,230 @@
+// Map files, so maps can ship as data rather than code. A map is a JSON object:
+//
+// {
+//   "name": "Tiny",                  optional
+//   "width": 16, "height": 16,       size of the grid the polygons are drawn on
+//   "background_color": 0,           optional
+//   "troops": 3,                     optional, starting troops for territories that don't give their own
+//   "territories": [
+//     {
+//       "name": "Peak",              optional
+//       "polygon": [17, 23, 39, 17], grid points, each row * width + column, ending where it started
+//       "center": 52,                grid point the troop count is drawn at
+//       "neighbors": [1, 2, 3],      indices into territories, normal borders
+//       "borders": [                 optional, borders of another kind
+//         { "to": 4, "kind": "AttackOnly" }   AttackOnly, FortifyOnly or DefendOnly
+//       ],
+//       "troops": 5,                 optional
+//       "neutral": 2                 optional, starts neutral with this many troops
+//     }
+//   ],
+//   "countries": [
+//     { "name": "North", "territories": [0, 1], "bonus": 2, "color": 16711680 }
+//   ]
+// }
+//
+// Every border is listed on both sides. A one way border is normal or AttackOnly on the side that
+// attacks, and DefendOnly on the other.
+//
+// Territories are filled with the color of their country.
+// maps/tiny.json is the built-in map in this format.
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::map::{Border, BorderKind, Country, Map, Territory, TerritoryState};
+
+pub(crate) const DEFAULT_TROOPS: u32 = 3;
+pub(crate) const BACKGROUND_INDEX: usize = 16777215;
+// For maps that don't color their own countries
+pub(crate) const COUNTRY_COLORS: [u32; 8] = [
+    0xFF0000, 0x00FFCC, 0x3366FF, 0xFFCC00, 0xCC33FF, 0x33CC33, 0xFF8800, 0x888888,
+];
+
+#[derive(Serialize, Deserialize)]
+struct MapFile {
+    #[serde(default)]
+    name: String,
+    width: u32,
+    height: u32,
+    #[serde(default)]
+    background_color: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    troops: Option<u32>,
+    territories: Vec<TerritoryFile>,
+    #[serde(default)]
+    countries: Vec<CountryFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TerritoryFile {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    name: String,
+    polygon: Vec<u32>,
+    center: u32,
+    #[serde(default)]
+    neighbors: Vec<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    borders: Vec<Border>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    troops: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    neutral: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CountryFile {
+    name: String,
+    territories: Vec<u32>,
+    #[serde(default)]
+    bonus: u32,
+    #[serde(default)]
+    color: u32,
+}
+
+// Only what stops the map from being built, see Map::validate for everything else
+#[derive(Debug)]
+pub enum MapError {
+    Json(serde_json::Error),
+    Xml(String),
+    NoTerritories,
+    UnknownNeighbor { territory: usize, neighbor: u32 },
+    UnknownTerritory { country: usize, territory: u32 },
+    OffGrid { territory: usize, point: u32 },
+    // Asked to generate more territories than fit on the grid
+    NoRoom { territories: u32, fit: u32 },
+    // More grid points than fit in a u32
+    TooLarge { width: u32, height: u32 },
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapError::Json(e) => write!(f, "invalid map file: {}", e),
+            MapError::Xml(e) => write!(f, "invalid map export: {}", e),
+            MapError::NoTerritories => write!(f, "the map has no territories"),
+            MapError::UnknownNeighbor { territory, neighbor } =>
+                write!(f, "territory {} borders territory {}, which doesn't exist", territory, neighbor),
+            MapError::UnknownTerritory { country, territory } =>
+                write!(f, "country {} lists territory {}, which doesn't exist", country, territory),
+            MapError::OffGrid { territory, point } =>
+                write!(f, "territory {} uses point {}, which is off the grid", territory, point),
+            MapError::NoRoom { territories, fit } =>
+                write!(f, "only {} of the {} territories fit on the grid", fit, territories),
+            MapError::TooLarge { width, height } => write!(f, "a {} by {} grid is too large", width, height),
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
+
+impl From<serde_json::Error> for MapError {
+    fn from(e: serde_json::Error) -> Self { MapError::Json(e) }
+}
+
+#[cfg(feature = "wasm")]
+impl From<MapError> for JsValue {
+    fn from(e: MapError) -> Self { JsValue::from_str(&e.to_string()) }
+}
+
+impl MapFile {
+    fn check(&self) -> Result<(), MapError> {
+        let count = self.territories.len();
+        if count == 0 { return Err(MapError::NoTerritories); }
+        let points = self.width.checked_mul(self.height)
+            .ok_or(MapError::TooLarge { width: self.width, height: self.height })?;
+        for (i, t) in self.territories.iter().enumerate() {
+            let mut neighbors = t.neighbors.iter().chain(t.borders.iter().map(|b| &b.to));
+            if let Some(neighbor) = neighbors.find(|n| **n as usize >= count) {
+                return Err(MapError::UnknownNeighbor { territory: i, neighbor: *neighbor });
+            }
+            if let Some(point) = t.polygon.iter().chain(std::iter::once(&t.center)).find(|p| **p >= points) {
+                return Err(MapError::OffGrid { territory: i, point: *point });
+            }
+        }
+        for (i, c) in self.countries.iter().enumerate() {
+            if let Some(territory) = c.territories.iter().find(|t| **t as usize >= count) {
+                return Err(MapError::UnknownTerritory { country: i, territory: *territory });
+            }
+        }
+        Ok(())
+    }
+
+    fn into_map(self) -> Map {
+        let default_troops = self.troops.unwrap_or(DEFAULT_TROOPS);
+        let countries = &self.countries;
+        let territories = self.territories.into_iter().enumerate().map(|(i, t)| Territory {
+            name: t.name,
+            vertices: t.polygon,
+            center: t.center,
+            color: countries.iter().find(|c| c.territories.contains(&(i as u32))).map(|c| c.color).unwrap_or(0),
+            troops: t.troops.unwrap_or(default_troops),
+            state: TerritoryState::Dormant,
+            borders: Border::normal(&t.neighbors).into_iter().chain(t.borders).collect(),
+            neutral: t.neutral,
+        }).collect();
+        Map {
+            name: self.name,
+            width: self.width,
+            height: self.height,
+            territories,
+            countries: self.countries.into_iter().map(|c| Country {
+                name: c.name,
+                territories: c.territories,
+                border_color: c.color,
+                bonus: c.bonus,
+            }).collect(),
+            background_color: self.background_color,
+            background_index: BACKGROUND_INDEX,
+            troops_to_place: 1,
+            troop_placement_cache: HashMap::new(),
+        }
+    }
+
+    fn from_map(map: &Map) -> MapFile {
+        MapFile {
+            name: map.name.clone(),
+            width: map.width,
+            height: map.height,
+            background_color: map.background_color,
+            troops: None,
+            territories: map.territories.iter().map(|t| TerritoryFile {
+                name: t.name.clone(),
+                polygon: t.vertices.clone(),
+                center: t.center,
+                neighbors: t.borders.iter().filter(|b| b.kind == BorderKind::Normal).map(|b| b.to).collect(),
+                borders: t.borders.iter().filter(|b| b.kind != BorderKind::Normal).copied().collect(),
+                troops: Some(t.troops),
+                neutral: t.neutral,
+            }).collect(),
+            countries: map.countries.iter().map(|c| CountryFile {
+                name: c.name.clone(),
+                territories: c.territories.clone(),
+                bonus: c.bonus,
+                color: c.border_color,
+            }).collect(),
+        }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Map {
+    // Reads a map file, see the format above
+    pub fn from_json(json: &str) -> Result<Map, MapError> {
+        let file: MapFile = serde_json::from_str(json)?;
+        file.check()?;
+        Ok(file.into_map())
+    }
+    // Writes the map out as a map file, with the troops currently on each territory
+    pub fn to_json(&self) -> Result<String, MapError> {
+        Ok(serde_json::to_string_pretty(&MapFile::from_map(self))?)
+    }
+}
+
+impl FromStr for Map {
+    type Err = MapError;
+    fn from_str(s: &str) -> Result<Map, MapError> { Map::from_json(s) }
+}
@@ -0,0 +1,234 @@
+// Random maps from a seed. The grid is tiled with hexagons as large as still leaves room for the
+// territories, a connected patch of them is grown from a random cell to become the territories, and
+// the territories are grown into countries from starting points spread across the patch:
+//
+//    /\/\/\
+//   | | | |      each hexagon is 4k points wide and 4k high, rows are 3k apart
+//    \/\/\/\     and every other row is shifted half a hexagon to the right
+//     | | | |
+//      \/\/\/
+//
+// Territories border every territory they share an edge with, and a country's bonus grows with its
+// size and with how many of its territories have to be defended.
+use std::collections::{HashMap, VecDeque};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+use rand::Rng;
+
+use crate::map::{Border, Country, Map, MapError, Territory, TerritoryState};
+use crate::map::format::{BACKGROUND_INDEX, COUNTRY_COLORS, DEFAULT_TROOPS};
+use crate::rng::GameRng;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct MapGenerator {
+    seed: u64,
+    width: u32,
+    height: u32,
+    territories: u32,
+    countries: u32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl MapGenerator {
+    pub fn new() -> MapGenerator {
+        MapGenerator { seed: 123, width: 64, height: 48, territories: 24, countries: 5 }
+    }
+
+    pub fn seed(mut self, seed: u64) -> MapGenerator {
+        self.seed = seed;
+        self
+    }
+    // Size of the grid the polygons are drawn on
+    pub fn size(mut self, width: u32, height: u32) -> MapGenerator {
+        self.width = width;
+        self.height = height;
+        self
+    }
+    pub fn territories(mut self, count: u32) -> MapGenerator {
+        self.territories = count;
+        self
+    }
+    // At least one and at most one per territory
+    pub fn countries(mut self, count: u32) -> MapGenerator {
+        self.countries = count;
+        self
+    }
+
+    // The same settings always give the same map
+    pub fn generate(&self) -> Result<Map, MapError> {
+        let count = self.territories as usize;
+        if count == 0 { return Err(MapError::NoTerritories); }
+        let scale = self.scale().ok_or(MapError::NoRoom { territories: self.territories, fit: self.cells(1).len() as u32 })?;
+        let mut rng = GameRng::seed_from_u64(self.seed);
+
+        let cells = self.cells(scale);
+        let adjacent = shared_edges(&cells.iter().map(|c| self.hexagon(*c, scale)).collect::<Vec<Vec<u32>>>());
+        let mut chosen = grow_patch(&adjacent, count, &mut rng);
+        // Numbered row by row, the order cells() lists them in
+        chosen.sort_unstable();
+        let index: HashMap<usize, usize> = chosen.iter().enumerate().map(|(i, c)| (*c, i)).collect();
+        let neighbors: Vec<Vec<usize>> = chosen.iter()
+            .map(|c| adjacent[*c].iter().filter_map(|n| index.get(n).copied()).collect())
+            .collect();
+
+        let country_count = self.countries.clamp(1, self.territories) as usize;
+        let country_of = grow_countries(&neighbors, country_count, &mut rng);
+        let countries: Vec<Country> = (0..country_count).map(|c| {
+            let members: Vec<usize> = (0..count).filter(|t| country_of[*t] == c).collect();
+            let defended = members.iter().filter(|t| neighbors[**t].iter().any(|n| country_of[*n] != c)).count();
+            Country {
+                name: format!("Country {}", c + 1),
+                territories: members.iter().map(|t| *t as u32).collect(),
+                border_color: COUNTRY_COLORS[c % COUNTRY_COLORS.len()],
+                bonus: std::cmp::max(1, (members.len() + defended).div_ceil(2) - 1) as u32,
+            }
+        }).collect();
+
+        let territories = chosen.iter().enumerate().map(|(i, cell)| Territory {
+            name: String::new(),
+            vertices: self.hexagon(cells[*cell], scale),
+            center: self.point(cells[*cell]),
+            color: countries[country_of[i]].border_color,
+            troops: DEFAULT_TROOPS,
+            state: TerritoryState::Dormant,
+            borders: Border::normal(&neighbors[i].iter().map(|n| *n as u32).collect::<Vec<u32>>()),
+            neutral: None,
+        }).collect();
+
+        Ok(Map {
+            name: format!("Random {}", self.seed),
+            width: self.width,
+            height: self.height,
+            territories,
+            countries,
+            background_color: 0,
+            background_index: BACKGROUND_INDEX,
+            troops_to_place: 1,
+            troop_placement_cache: HashMap::new(),
+        })
+    }
+}
+
+impl MapGenerator {
+    // The largest hexagons that leave half as many cells again as there are territories, so the patch
+    // has some shape to it, or failing that just enough cells
+    fn scale(&self) -> Option<u32> {
+        let largest = std::cmp::min(self.width, self.height) / 4;
+        let fits = |room: u32| (1..=largest).rev().find(|k| self.cells(*k).len() as u32 >= room);
+        fits(self.territories + self.territories / 2).or_else(|| fits(self.territories))
+    }
+
+    // Centers of every hexagon that fits on the grid, row by row. A grid too narrow for the shifted
+    // rows only gets the first row, the rows either side of a missing one wouldn't touch.
+    fn cells(&self, k: u32) -> Vec<(u32, u32)> {
+        let mut cells = vec!();
+        let mut y = 2 * k;
+        let mut row = 0;
+        while y + 2 * k < self.height && (row == 0 || 6 * k < self.width) {
+            let mut x = if row % 2 == 0 { 2 * k } else { 4 * k };
+            while x + 2 * k < self.width {
+                cells.push((x, y));
+                x += 4 * k;
+            }
+            y += 3 * k;
+            row += 1;
+        }
+        cells
+    }
+
+    fn point(&self, (x, y): (u32, u32)) -> u32 { y * self.width + x }
+
+    fn hexagon(&self, (x, y): (u32, u32), k: u32) -> Vec<u32> {
+        let corners = [(x, y - 2 * k), (x + 2 * k, y - k), (x + 2 * k, y + k), (x, y + 2 * k), (x - 2 * k, y + k), (x - 2 * k, y - k), (x, y - 2 * k)];
+        corners.iter().map(|c| self.point(*c)).collect()
+    }
+}
+
+impl Default for MapGenerator {
+    fn default() -> Self { MapGenerator::new() }
+}
+
+// For each polygon, the polygons it shares an edge with
+fn shared_edges(polygons: &[Vec<u32>]) -> Vec<Vec<usize>> {
+    let mut edges: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+    for (i, polygon) in polygons.iter().enumerate() {
+        for edge in polygon.windows(2) {
+            let key = (std::cmp::min(edge[0], edge[1]), std::cmp::max(edge[0], edge[1]));
+            edges.entry(key).or_default().push(i);
+        }
+    }
+    let mut adjacent = vec![vec!(); polygons.len()];
+    for sharing in edges.values() {
+        for a in sharing {
+            for b in sharing.iter().filter(|b| *b != a) {
+                if !adjacent[*a].contains(b) { adjacent[*a].push(*b); }
+            }
+        }
+    }
+    adjacent.iter_mut().for_each(|a: &mut Vec<usize>| a.sort_unstable());
+    adjacent
+}
+
+// A connected set of cells, grown one random cell of its edge at a time
+fn grow_patch(adjacent: &[Vec<usize>], count: usize, rng: &mut GameRng) -> Vec<usize> {
+    let mut chosen = vec![rng.gen_range(0, adjacent.len())];
+    let mut edge: Vec<usize> = adjacent[chosen[0]].clone();
+    while chosen.len() < count {
+        let cell = edge.swap_remove(rng.gen_range(0, edge.len()));
+        chosen.push(cell);
+        for n in &adjacent[cell] {
+            if !chosen.contains(n) && !edge.contains(n) { edge.push(*n); }
+        }
+    }
+    chosen
+}
+
+// The country of each territory. Countries start as far apart as they can and the smallest one takes a
+// neighboring territory until none are left, so every country is connected and sizes stay close.
+fn grow_countries(neighbors: &[Vec<usize>], count: usize, rng: &mut GameRng) -> Vec<usize> {
+    let territories = neighbors.len();
+    let mut starts = vec![rng.gen_range(0, territories)];
+    while starts.len() < count {
+        let distance = distances(neighbors, &starts);
+        let farthest = (0..territories).filter(|t| !starts.contains(t)).max_by_key(|t| (distance[*t], territories - t));
+        starts.extend(farthest);
+    }
+    let mut country_of = vec![usize::MAX; territories];
+    let mut sizes = vec![1; count];
+    starts.iter().enumerate().for_each(|(c, t)| country_of[*t] = c);
+    for _ in count..territories {
+        let mut by_size: Vec<usize> = (0..count).collect();
+        by_size.sort_by_key(|c| sizes[*c]);
+        for c in by_size {
+            let open: Vec<usize> = (0..territories)
+                .filter(|t| country_of[*t] == usize::MAX && neighbors[*t].iter().any(|n| country_of[*n] == c))
+                .collect();
+            if !open.is_empty() {
+                country_of[open[rng.gen_range(0, open.len())]] = c;
+                sizes[c] += 1;
+                break;
+            }
+        }
+    }
+    country_of
+}
+
+// Steps from the nearest of the starting territories
+fn distances(neighbors: &[Vec<usize>], starts: &[usize]) -> Vec<usize> {
+    let mut distance = vec![usize::MAX; neighbors.len()];
+    let mut queue = VecDeque::new();
+    for s in starts {
+        distance[*s] = 0;
+        queue.push_back(*s);
+    }
+    while let Some(t) = queue.pop_front() {
+        for n in &neighbors[t] {
+            if distance[*n] == usize::MAX {
+                distance[*n] = distance[t] + 1;
+                queue.push_back(*n);
+            }
+        }
+    }
+    distance
+}
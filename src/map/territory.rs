@@ -1,21 +1,30 @@
-extern crate web_sys;
-
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::map::border::Border;
 
-#[wasm_bindgen]
-#[derive(Clone)]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Territory {
+    #[serde(default)]
+    pub(crate) name: String,
     pub(crate) vertices: Vec<u32>,
     pub center: u32,
     pub color: u32,
     pub troops: u32,
     pub(crate) state: TerritoryState,
-    pub(crate) neighbors: Vec<u32>,
+    // Saves from before typed borders have a plain list of neighbors instead
+    #[serde(alias = "neighbors", deserialize_with = "crate::map::border::saved_borders")]
+    pub(crate) borders: Vec<Border>,
+    // Troops it starts the game with as a neutral territory, None for one dealt to the players
+    #[serde(default)]
+    pub(crate) neutral: Option<u32>,
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 #[repr(u8)]
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum TerritoryState {
     Dormant = 0,
     Selected = 1,
@@ -23,7 +32,7 @@ pub enum TerritoryState {
     Highlighted = 3
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl Territory {
     pub fn is_selected(&self) -> bool { self.state == TerritoryState::Selected }
     pub fn is_targeted(&self) -> bool { self.state == TerritoryState::Targeted }
@@ -32,11 +41,11 @@ impl Territory {
             self.is_selected() || self.is_targeted()
     }
     pub fn set_troops(&mut self, troops: u32) -> () { self.troops = troops }
-    pub fn add_troops(&mut self, troops: u32) -> () { self.troops = self.troops + troops }
+    pub fn add_troops(&mut self, troops: u32) -> () { self.troops += troops }
     pub fn sub_troops(&mut self, troops: u32) -> () {
-        let new_troops = self.troops - troops;
-        self.troops = new_troops
+        self.troops = self.troops.saturating_sub(troops)
     }
     pub fn vertices(&self) -> *const u32 { self.vertices.as_ptr() }
     pub fn troops(&self) -> u32 { self.troops }
+    pub fn starts_neutral(&self) -> bool { self.neutral.is_some() }
 }
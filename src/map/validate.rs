@@ -0,0 +1,118 @@
+use std::collections::VecDeque;
+use std::fmt;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::map::Map;
+
+// Something wrong with a map that the game would otherwise trip over or quietly get wrong
+#[derive(Clone, Debug, PartialEq)]
+pub enum MapProblem {
+    // The territory borders the neighbor but the neighbor has no border back, not even a DefendOnly one
+    OneSidedBorder { territory: usize, neighbor: usize },
+    UnknownNeighbor { territory: usize, neighbor: u32 },
+    BordersItself { territory: usize },
+    // Territories that can't be reached from the first one
+    Disconnected { territories: Vec<usize> },
+    NoCountry { territory: usize },
+    SeveralCountries { territory: usize, countries: Vec<usize> },
+    EmptyCountry { country: usize },
+    UnknownTerritory { country: usize, territory: u32 },
+    // Fewer than three corners, or the last point doesn't lead back to the first
+    OpenPolygon { territory: usize },
+}
+
+impl fmt::Display for MapProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapProblem::OneSidedBorder { territory, neighbor } =>
+                write!(f, "territory {} borders territory {}, but not the other way around", territory, neighbor),
+            MapProblem::UnknownNeighbor { territory, neighbor } =>
+                write!(f, "territory {} borders territory {}, which doesn't exist", territory, neighbor),
+            MapProblem::BordersItself { territory } => write!(f, "territory {} borders itself", territory),
+            MapProblem::Disconnected { territories } =>
+                write!(f, "territories {:?} can't be reached from territory 0", territories),
+            MapProblem::NoCountry { territory } => write!(f, "territory {} isn't in any country", territory),
+            MapProblem::SeveralCountries { territory, countries } =>
+                write!(f, "territory {} is in several countries: {:?}", territory, countries),
+            MapProblem::EmptyCountry { country } => write!(f, "country {} has no territories", country),
+            MapProblem::UnknownTerritory { country, territory } =>
+                write!(f, "country {} lists territory {}, which doesn't exist", country, territory),
+            MapProblem::OpenPolygon { territory } => write!(f, "the outline of territory {} isn't closed", territory),
+        }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Map {
+    // validate, as messages
+    pub fn describe_problems(&self) -> Vec<String> {
+        self.validate().iter().map(|p| p.to_string()).collect()
+    }
+}
+
+impl Map {
+    // Every problem found, in territory then country order. Empty for a sound map.
+    pub fn validate(&self) -> Vec<MapProblem> {
+        let count = self.territories.len();
+        let mut problems = vec!();
+        for (i, t) in self.territories.iter().enumerate() {
+            for b in &t.borders {
+                let neighbor = b.to as usize;
+                if neighbor >= count {
+                    problems.push(MapProblem::UnknownNeighbor { territory: i, neighbor: b.to });
+                } else if neighbor == i {
+                    problems.push(MapProblem::BordersItself { territory: i });
+                } else if !self.territories[neighbor].borders.iter().any(|back| back.to as usize == i) {
+                    problems.push(MapProblem::OneSidedBorder { territory: i, neighbor });
+                }
+            }
+            let corners = t.vertices.len().saturating_sub(1);
+            if corners < 3 || t.vertices.first() != t.vertices.last() {
+                problems.push(MapProblem::OpenPolygon { territory: i });
+            }
+            let countries: Vec<usize> = self.countries.iter().enumerate()
+                .filter(|(_, c)| c.territories.contains(&(i as u32)))
+                .map(|(c, _)| c)
+                .collect();
+            match countries.len() {
+                0 => problems.push(MapProblem::NoCountry { territory: i }),
+                1 => (),
+                _ => problems.push(MapProblem::SeveralCountries { territory: i, countries }),
+            }
+        }
+        let unreachable = self.unreachable();
+        if !unreachable.is_empty() {
+            problems.push(MapProblem::Disconnected { territories: unreachable });
+        }
+        for (c, country) in self.countries.iter().enumerate() {
+            if country.territories.is_empty() {
+                problems.push(MapProblem::EmptyCountry { country: c });
+            }
+            for t in country.territories.iter().filter(|t| **t as usize >= count) {
+                problems.push(MapProblem::UnknownTerritory { country: c, territory: *t });
+            }
+        }
+        problems
+    }
+
+    // Borders of any kind count both ways here, one sided ones are reported on their own
+    fn unreachable(&self) -> Vec<usize> {
+        let count = self.territories.len();
+        if count == 0 { return vec!(); }
+        let mut reached = vec![false; count];
+        reached[0] = true;
+        let mut queue = VecDeque::from(vec!(0));
+        while let Some(t) = queue.pop_front() {
+            let borders = self.territories[t].borders.iter().map(|b| b.to as usize)
+                .chain((0..count).filter(|o| self.territories[*o].borders.iter().any(|b| b.to as usize == t)));
+            for n in borders.filter(|n| *n < count).collect::<Vec<usize>>() {
+                if !reached[n] {
+                    reached[n] = true;
+                    queue.push_back(n);
+                }
+            }
+        }
+        (0..count).filter(|t| !reached[*t]).collect()
+    }
+}
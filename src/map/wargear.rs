@@ -0,0 +1,226 @@
+// Imports the XML export of a WarGear board:
+//
+// <board boardname="Tiny" width="400" height="300">
+//   <territories>
+//     <territory tid="7" name="Peak" xpos="120" ypos="45"/>
+//   </territories>
+//   <borders>
+//     <border fromid="7" toid="8" direction="Two-way"/>
+//     <border fromid="8" toid="9" direction="One-way" type="Artillery"/>
+//   </borders>
+//   <continents>
+//     <continent cid="1" name="North" bonus="2" members="7,8"/>
+//     <continent cid="2" name="South" bonus="3"><member tid="9"/></continent>
+//   </continents>
+// </board>
+//
+// The export has no territory shapes, so each territory gets a small diamond around its position on a
+// grid of one point per POINT_PIXELS pixels. Artillery and fortify only borders keep their kind, and the
+// far end of a one way border gets a DefendOnly border back, unless a one way border
+// runs the other way too. Everything else the engine can't play yet is left out
+// and listed in the import's unsupported().
+use std::collections::HashMap;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::map::{Border, BorderKind, Country, Map, MapError, Territory, TerritoryState};
+use crate::map::format::{BACKGROUND_INDEX, COUNTRY_COLORS, DEFAULT_TROOPS};
+use roxmltree::Node;
+
+const POINT_PIXELS: u32 = 10;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub struct WarGearImport {
+    pub(crate) map: Map,
+    pub(crate) unsupported: Vec<String>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl WarGearImport {
+    pub fn map(&self) -> Map { self.map.clone() }
+    // One line for each feature of the board that was left out or changed
+    pub fn unsupported(&self) -> Vec<String> { self.unsupported.clone() }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Map {
+    pub fn from_wargear(xml: &str) -> Result<WarGearImport, MapError> {
+        // Board exports often carry a doctype
+        let options = roxmltree::ParsingOptions { allow_dtd: true, ..Default::default() };
+        let document = roxmltree::Document::parse_with_options(xml, options)
+            .map_err(|e| MapError::Xml(e.to_string()))?;
+        Importer::default().import(document.root_element())
+    }
+}
+
+// Element and attribute names are matched ignoring case, exports differ
+fn attribute<'a>(element: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    element.attributes().find(|a| a.name().eq_ignore_ascii_case(name)).map(|a| a.value())
+}
+
+fn descendants<'a, 'input>(element: Node<'a, 'input>, name: &str) -> Vec<Node<'a, 'input>> {
+    element.descendants()
+        .filter(|n| n.is_element() && *n != element && n.tag_name().name().eq_ignore_ascii_case(name))
+        .collect()
+}
+
+#[derive(Default)]
+struct Importer {
+    unsupported: Vec<String>,
+}
+
+impl Importer {
+    fn report(&mut self, message: String) -> () {
+        if !self.unsupported.contains(&message) { self.unsupported.push(message); }
+    }
+
+    // Anything on the element besides the attributes we read
+    fn report_attributes(&mut self, element: Node, known: &[&str]) -> () {
+        let mut extra: Vec<&str> = element.attributes().map(|a| a.name())
+            .filter(|k| !known.iter().any(|known| k.eq_ignore_ascii_case(known)))
+            .collect();
+        extra.sort();
+        for attribute in extra {
+            self.report(format!("{} setting `{}`", element.tag_name().name().to_lowercase(), attribute));
+        }
+    }
+
+    fn report_elements(&mut self, element: Node) -> () {
+        const KNOWN: [&str; 8] = ["board", "territories", "territory", "borders", "border", "continents", "continent", "member"];
+        for child in element.children().filter(|n| n.is_element()) {
+            let name = child.tag_name().name();
+            if KNOWN.iter().any(|k| name.eq_ignore_ascii_case(k)) {
+                self.report_elements(child);
+            } else {
+                self.report(format!("`{}` elements", name));
+            }
+        }
+    }
+
+    fn number(element: Node, name: &str) -> Result<u32, MapError> {
+        let tag = element.tag_name().name();
+        let value = attribute(element, name)
+            .ok_or_else(|| MapError::Xml(format!("a {} is missing its {}", tag, name)))?;
+        value.trim().parse::<f64>().ok().map(|n| n.round()).filter(|n| (0.0..=u32::MAX as f64).contains(n)).map(|n| n as u32)
+            .ok_or_else(|| MapError::Xml(format!("a {} has {}=\"{}\", which isn't a number from 0 to {}", tag, name, value, u32::MAX)))
+    }
+
+    fn import(mut self, root: Node) -> Result<WarGearImport, MapError> {
+        let board = if root.tag_name().name().eq_ignore_ascii_case("board") {
+            root
+        } else {
+            descendants(root, "board").into_iter().next().unwrap_or(root)
+        };
+        self.report_attributes(board, &["boardname", "name", "width", "height"]);
+        self.report_elements(root);
+
+        let territories = descendants(root, "territory");
+        if territories.is_empty() { return Err(MapError::NoTerritories); }
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        let mut positions = vec!();
+        for (i, t) in territories.iter().enumerate() {
+            self.report_attributes(*t, &["tid", "name", "xpos", "ypos"]);
+            let tid = attribute(*t, "tid").ok_or_else(|| MapError::Xml(String::from("a territory is missing its tid")))?;
+            if index.insert(tid, i).is_some() {
+                return Err(MapError::Xml(format!("territory {} appears more than once", tid)));
+            }
+            positions.push((Importer::number(*t, "xpos")? / POINT_PIXELS, Importer::number(*t, "ypos")? / POINT_PIXELS));
+        }
+
+        // A point of margin all round so every diamond fits
+        let board_points = |attribute: &str| Importer::number(board, attribute).ok().map(|pixels| pixels / POINT_PIXELS);
+        let width = std::cmp::max(board_points("width").unwrap_or(0), positions.iter().map(|p| p.0).max().unwrap_or(0)) + 3;
+        let height = std::cmp::max(board_points("height").unwrap_or(0), positions.iter().map(|p| p.1).max().unwrap_or(0)) + 3;
+        // Every point is numbered below width * height, so once that fits the diamonds do too
+        if width.checked_mul(height).is_none() { return Err(MapError::TooLarge { width, height }); }
+        self.report(String::from("territory shapes, which aren't in the export, each territory is drawn as a diamond"));
+
+        let lookup = |element: Node, name: &str| -> Result<usize, MapError> {
+            let tag = element.tag_name().name();
+            let tid = attribute(element, name)
+                .ok_or_else(|| MapError::Xml(format!("a {} is missing its {}", tag, name)))?;
+            index.get(tid).copied()
+                .ok_or_else(|| MapError::Xml(format!("a {} refers to territory {}, which doesn't exist", tag, tid)))
+        };
+
+        let mut borders: Vec<Vec<Border>> = vec![vec!(); territories.len()];
+        // The first border between two territories wins, except over the DefendOnly end of a one way
+        // border, so two opposite one way borders make a two way border
+        let mut add = |from: usize, to: usize, kind: BorderKind| {
+            match borders[from].iter_mut().find(|b| b.to as usize == to) {
+                Some(border) => if border.kind == BorderKind::DefendOnly { border.kind = kind; },
+                None => borders[from].push(Border::new(to as u32, kind)),
+            }
+        };
+        for border in descendants(root, "border") {
+            self.report_attributes(border, &["fromid", "toid", "direction", "type"]);
+            let (from, to) = (lookup(border, "fromid")?, lookup(border, "toid")?);
+            let name = attribute(border, "type").unwrap_or("Default");
+            let kind = match name.to_lowercase().replace(['-', '_'], " ").as_str() {
+                "default" | "normal" | "standard" => BorderKind::Normal,
+                "artillery" | "attack only" => BorderKind::AttackOnly,
+                "fortify only" => BorderKind::FortifyOnly,
+                _ => {
+                    self.report(format!("{} borders, which were left out", name));
+                    continue;
+                },
+            };
+            let one_way = attribute(border, "direction")
+                .is_some_and(|d| d.to_lowercase().contains("one") || d.trim() == "1");
+            add(from, to, kind);
+            add(to, from, if one_way { BorderKind::DefendOnly } else { kind });
+        }
+
+        let mut countries = vec!();
+        for continent in descendants(root, "continent") {
+            self.report_attributes(continent, &["cid", "name", "bonus", "members"]);
+            let mut members = vec!();
+            if let Some(list) = attribute(continent, "members") {
+                for tid in list.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()) {
+                    let t = index.get(tid).copied()
+                        .ok_or_else(|| MapError::Xml(format!("continent lists territory {}, which doesn't exist", tid)))?;
+                    members.push(t as u32);
+                }
+            }
+            for member in descendants(continent, "member") {
+                members.push(lookup(member, "tid")? as u32);
+            }
+            let bonus = attribute(continent, "bonus").and_then(|b| b.trim().parse::<i64>().ok()).unwrap_or(0);
+            if bonus < 0 { self.report(String::from("negative continent bonuses, which count as 0")); }
+            countries.push(Country {
+                name: attribute(continent, "name").unwrap_or_default().to_string(),
+                territories: members,
+                border_color: COUNTRY_COLORS[countries.len() % COUNTRY_COLORS.len()],
+                bonus: std::cmp::max(bonus, 0) as u32,
+            });
+        }
+
+        let territories = territories.iter().enumerate().map(|(i, t)| {
+            let (x, y) = (positions[i].0 + 1, positions[i].1 + 1);
+            let point = |x: u32, y: u32| y * width + x;
+            Territory {
+                name: attribute(*t, "name").unwrap_or_default().to_string(),
+                vertices: vec![point(x, y - 1), point(x + 1, y), point(x, y + 1), point(x - 1, y), point(x, y - 1)],
+                center: point(x, y),
+                color: countries.iter().find(|c: &&Country| c.territories.contains(&(i as u32))).map(|c| c.border_color).unwrap_or(0),
+                troops: DEFAULT_TROOPS,
+                state: TerritoryState::Dormant,
+                borders: std::mem::take(&mut borders[i]),
+                neutral: None,
+            }
+        }).collect();
+
+        let map = Map {
+            name: attribute(board, "boardname").or_else(|| attribute(board, "name")).unwrap_or_default().to_string(),
+            width,
+            height,
+            territories,
+            countries,
+            background_color: 0,
+            background_index: BACKGROUND_INDEX,
+            troops_to_place: 1,
+            troop_placement_cache: HashMap::new(),
+        };
+        Ok(WarGearImport { map, unsupported: self.unsupported })
+    }
+}
@@ -0,0 +1,54 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+use rand::Rng;
+
+use crate::Game;
+
+pub const NEUTRAL_COLOR: u32 = 0xBBBBBB;
+
+// Neutral territories belong to nobody. They never take a turn or attack, don't count towards
+// active_players or the end of the game, and are attacked and captured like any other territory.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Game {
+    pub fn is_neutral(&self, territory: usize) -> bool {
+        self.map.territories.get(territory).is_some_and(|t| t.neutral.is_some()) && self.territory_owner(territory).is_none()
+    }
+    pub fn neutral_territories(&self) -> Vec<u32> {
+        (0..self.map.territories.len()).filter(|t| self.is_neutral(*t)).map(|t| t as u32).collect()
+    }
+}
+
+impl Game {
+    // The map's neutral territories and any the config adds
+    pub(crate) fn mark_neutrals(&mut self) -> () {
+        for territory in 0..self.map.territories.len() {
+            self.map.territories[territory].neutral = self.config.neutral_troops(territory);
+        }
+    }
+
+    // The classic two player variant: a third army gets as many territories as each player
+    pub(crate) fn deal_neutral_army(&mut self) -> () {
+        let troops = match self.config.neutral_army {
+            Some(troops) => troops,
+            None => return,
+        };
+        let mut dealable: Vec<usize> = (0..self.map.territories.len())
+            .filter(|t| self.map.territories[*t].neutral.is_none())
+            .collect();
+        let share = dealable.len() / (self.players.len() + 1);
+        for _ in 0..share {
+            let territory = dealable.remove(self.rng.gen_range(0, dealable.len()));
+            self.map.territories[territory].neutral = Some(troops);
+        }
+    }
+
+    pub(crate) fn garrison_neutrals(&mut self) -> () {
+        self.map.territories.iter_mut().for_each(|t| if let Some(troops) = t.neutral { t.troops = troops });
+    }
+
+    pub(crate) fn color_neutrals(&mut self) -> () {
+        for territory in self.neutral_territories() {
+            self.map.set_color_for(territory as usize, NEUTRAL_COLOR as usize);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::Game;
+use crate::action::GameError;
+use crate::dice::DiceRules;
+
+// Battles with at least this many troops on both sides together are kept around once worked out
+const CACHE_FROM_TROOPS: u32 = 20;
+const CACHE_LIMIT: usize = 512;
+
+// (attacker losses, defender losses, chance) for one result of a roll
+type RollOutcome = (u32, u32, f64);
+type RollTable = Rc<Vec<RollOutcome>>;
+
+thread_local! {
+    static CACHE: RefCell<HashMap<(DiceRules, u32, u32), Rc<BattleOdds>>> = RefCell::new(HashMap::new());
+    static ROLLS: RefCell<HashMap<(DiceRules, u32, u32), RollTable>> = RefCell::new(HashMap::new());
+}
+
+// How a fight between the given troops ends when both sides roll until one is wiped out,
+// as roll_all does
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BattleOdds {
+    // Chance of the attacker ending with 0..=attackers troops, and likewise for the defender
+    pub(crate) attacker_survivors: Vec<f64>,
+    pub(crate) defender_survivors: Vec<f64>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl BattleOdds {
+    pub fn attacker_win(&self) -> f64 { 1.0 - self.attacker_survivors[0] }
+    pub fn defender_win(&self) -> f64 { self.attacker_survivors[0] }
+    pub fn expected_attackers(&self) -> f64 { expected(&self.attacker_survivors) }
+    pub fn expected_defenders(&self) -> f64 { expected(&self.defender_survivors) }
+    // Indexed by troops left, so [0] is the chance of losing everything
+    pub fn attacker_survivors(&self) -> Vec<f64> { self.attacker_survivors.clone() }
+    pub fn defender_survivors(&self) -> Vec<f64> { self.defender_survivors.clone() }
+}
+
+fn expected(distribution: &[f64]) -> f64 {
+    distribution.iter().enumerate().map(|(troops, p)| troops as f64 * p).sum()
+}
+
+fn roll_outcomes(rules: &DiceRules, attack_dice: u32, defense_dice: u32) -> RollTable {
+    ROLLS.with(|rolls| rolls.borrow_mut()
+        .entry((*rules, attack_dice, defense_dice))
+        .or_insert_with(|| Rc::new(count_roll_outcomes(rules, attack_dice, defense_dice)))
+        .clone())
+}
+
+// Goes through every way the dice can land
+fn count_roll_outcomes(rules: &DiceRules, attack_dice: u32, defense_dice: u32) -> Vec<RollOutcome> {
+    let die_faces = rules.faces as u32;
+    let dice = attack_dice + defense_dice;
+    let rolls = die_faces.pow(dice);
+    let mut counts: HashMap<(u32, u32), u32> = HashMap::new();
+    for roll in 0..rolls {
+        let faces: Vec<u32> = (0..dice).map(|i| roll / die_faces.pow(i) % die_faces).collect();
+        let mut attacks = faces[..attack_dice as usize].to_vec();
+        let mut defenses = faces[attack_dice as usize..].to_vec();
+        attacks.sort_unstable_by(|a, b| b.cmp(a));
+        defenses.sort_unstable_by(|a, b| b.cmp(a));
+        let (mut attack_losses, mut defend_losses) = (0, 0);
+        for (attack, defend) in attacks.iter().zip(defenses.iter()) {
+            if attack > defend || (attack == defend && rules.ties_to_attacker) {
+                defend_losses += 1
+            } else {
+                attack_losses += 1
+            }
+        }
+        *counts.entry((attack_losses, defend_losses)).or_insert(0) += 1;
+    }
+    counts.into_iter().map(|((a, d), n)| (a, d, n as f64 / rolls as f64)).collect()
+}
+
+// Markov chain over the troops left on each side, walked from the start until one side is out
+fn solve(rules: &DiceRules, attackers: u32, defenders: u32) -> BattleOdds {
+    let (a_max, d_max) = (attackers as usize, defenders as usize);
+    let mut mass = vec![vec![0.0; d_max + 1]; a_max + 1];
+    mass[a_max][d_max] = 1.0;
+    let mut attacker_survivors = vec![0.0; a_max + 1];
+    let mut defender_survivors = vec![0.0; d_max + 1];
+
+    // Every roll takes at least one troop, so going by troops left visits each state after all its sources
+    for total in (0..=a_max + d_max).rev() {
+        for a in total.saturating_sub(d_max)..=std::cmp::min(total, a_max) {
+            let d = total - a;
+            let p = mass[a][d];
+            if p == 0.0 { continue; }
+            if a == 0 || d == 0 {
+                attacker_survivors[a] += p;
+                defender_survivors[d] += p;
+                continue;
+            }
+            let outcomes = roll_outcomes(rules, std::cmp::min(a as u32, rules.attack_dice), std::cmp::min(d as u32, rules.defense_dice));
+            for (attack_losses, defend_losses, chance) in outcomes.iter() {
+                mass[a - *attack_losses as usize][d - *defend_losses as usize] += p * chance;
+            }
+        }
+    }
+    BattleOdds { attacker_survivors, defender_survivors }
+}
+
+// Odds with the classic dice
+pub fn odds(attackers: u32, defenders: u32) -> Rc<BattleOdds> {
+    odds_with(&DiceRules::default(), attackers, defenders)
+}
+
+// Balanced blitz keeps each side's chance of winning, and shares it out over the troops the winner
+// keeps by the square of each result's chance
+fn balance(odds: BattleOdds) -> BattleOdds {
+    let share = |survivors: &[f64]| -> Vec<f64> {
+        let win: f64 = survivors[1..].iter().sum();
+        let squares: f64 = survivors[1..].iter().map(|p| p * p).sum();
+        std::iter::once(survivors[0])
+            .chain(survivors[1..].iter().map(|p| if squares > 0.0 { win * p * p / squares } else { 0.0 }))
+            .collect()
+    };
+    BattleOdds { attacker_survivors: share(&odds.attacker_survivors), defender_survivors: share(&odds.defender_survivors) }
+}
+
+fn solve_with(rules: &DiceRules, attackers: u32, defenders: u32) -> BattleOdds {
+    let odds = solve(rules, attackers, defenders);
+    if rules.balanced_blitz { balance(odds) } else { odds }
+}
+
+// The dice limits are the most each side rolls in this battle, after the players' own choices.
+// With balanced blitz these are the odds of a blitz, see Game::balanced_blitz.
+pub fn odds_with(rules: &DiceRules, attackers: u32, defenders: u32) -> Rc<BattleOdds> {
+    if attackers + defenders < CACHE_FROM_TROOPS { return Rc::new(solve_with(rules, attackers, defenders)); }
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if let Some(odds) = cache.get(&(*rules, attackers, defenders)) { return odds.clone(); }
+        if cache.len() >= CACHE_LIMIT { cache.clear(); }
+        let odds = Rc::new(solve_with(rules, attackers, defenders));
+        cache.insert((*rules, attackers, defenders), odds.clone());
+        odds
+    })
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn battle_odds(attackers: u32, defenders: u32) -> BattleOdds {
+    odds(attackers, defenders).as_ref().clone()
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Game {
+    // Odds of attacking with everything but the one troop that has to stay behind
+    pub fn attack_odds(&self, from: usize, to: usize) -> Result<BattleOdds, GameError> {
+        let attackers = self.map.territories.get(from).ok_or(GameError::UnknownTerritory)?.troops.saturating_sub(1);
+        self.attack_odds_with(from, to, attackers)
+    }
+    // Odds of attacking with some of the troops, under the game's dice rules
+    pub fn attack_odds_with(&self, from: usize, to: usize, attackers: u32) -> Result<BattleOdds, GameError> {
+        if from >= self.map.territories.len() || to >= self.map.territories.len() {
+            return Err(GameError::UnknownTerritory);
+        }
+        let attacker = self.territory_owner(from).map(|p| p as usize).unwrap_or_else(|| self.on_player_index());
+        let defender = self.territory_owner(to).map(|p| p as usize);
+        let rules = self.battle_dice(attacker, defender);
+        Ok(odds_with(&rules, attackers, self.map.territories[to].troops).as_ref().clone())
+    }
+    // Odds for the territories picked on the map
+    pub fn selection_odds(&self) -> Result<BattleOdds, GameError> {
+        match (self.selected_territory_index(), self.targeted_territory_index()) {
+            (Some(from), Some(to)) => self.attack_odds(from, to),
+            _ => Err(GameError::NoSelection),
+        }
+    }
+    pub fn selection_odds_with(&self, attackers: u32) -> Result<BattleOdds, GameError> {
+        match (self.selected_territory_index(), self.targeted_territory_index()) {
+            (Some(from), Some(to)) => self.attack_odds_with(from, to, attackers),
+            _ => Err(GameError::NoSelection),
+        }
+    }
+}
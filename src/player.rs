@@ -1,12 +1,45 @@
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
-#[wasm_bindgen]
-#[derive(Clone, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+use crate::ai::Bot;
+use crate::cards::Card;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Player {
     pub index: u32,
     pub color: u32,
+    #[serde(default)]
+    pub(crate) name: String,
     pub(crate) territories: Vec<u32>,
+    pub(crate) cards: Vec<Card>,
+    #[serde(default)]
+    pub(crate) bot: Option<Bot>,
+    // Dice chosen by the player, None to roll as many as the rules allow
+    #[serde(default)]
+    pub(crate) attack_dice: Option<u32>,
+    #[serde(default)]
+    pub(crate) defense_dice: Option<u32>,
+    // Starting troops still to deploy during setup
+    #[serde(default)]
+    pub(crate) reserve: u32,
 }
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Elimination {
+    pub player: u32,
+    pub eliminated_by: u32,
+    pub turn: u32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Player {
+    pub fn name(&self) -> String { self.name.clone() }
+    pub fn is_ai(&self) -> bool { self.bot.is_some() }
+}
+
 impl Player {
     pub fn capture_territory(&mut self, territory_index: u32) -> () {
         self.territories.push(territory_index);
@@ -0,0 +1,162 @@
+use std::fmt;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::Game;
+use crate::action::{Action, GameError};
+use crate::attack::BattleLog;
+use crate::config::{ConfigError, GameConfig};
+use crate::history::History;
+
+pub const REPLAY_VERSION: u32 = 1;
+
+// Everything needed to play a game back: the seeded setup and the actions taken
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ReplayFile {
+    pub version: u32,
+    pub config: GameConfig,
+    pub actions: Vec<Action>,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Json(serde_json::Error),
+    UnsupportedVersion(u32),
+    Config(ConfigError),
+    InvalidAction { index: usize, error: GameError },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Json(e) => write!(f, "invalid replay: {}", e),
+            ReplayError::UnsupportedVersion(v) =>
+                write!(f, "replay version {} is not supported (expected {})", v, REPLAY_VERSION),
+            ReplayError::Config(e) => write!(f, "invalid replay setup: {}", e),
+            ReplayError::InvalidAction { index, error } => write!(f, "action {} cannot be replayed: {}", index, error),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<serde_json::Error> for ReplayError {
+    fn from(e: serde_json::Error) -> Self { ReplayError::Json(e) }
+}
+
+#[cfg(feature = "wasm")]
+impl From<ReplayError> for JsValue {
+    fn from(e: ReplayError) -> Self { JsValue::from_str(&e.to_string()) }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub struct Replay {
+    file: ReplayFile,
+    // Action index and game state at the start of every turn, for jumping around. The states are kept
+    // without their history, seek rebuilds it from the file and the battles
+    turn_starts: Vec<(usize, Game)>,
+    battles: Vec<BattleLog>,
+    position: usize,
+    current: Game,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Replay {
+    pub fn from_json(json: &str) -> Result<Replay, ReplayError> {
+        Replay::new(serde_json::from_str(json)?)
+    }
+    pub fn to_json(&self) -> Result<String, ReplayError> {
+        Ok(serde_json::to_string(&self.file)?)
+    }
+
+    // Actions applied to reach the current state
+    pub fn position(&self) -> usize { self.position }
+    pub fn action_count(&self) -> usize { self.file.actions.len() }
+    pub fn turn(&self) -> u32 { self.current.turn_number() }
+    pub fn turn_count(&self) -> u32 {
+        self.turn_starts.last().map(|(_, game)| game.turn_number()).unwrap_or(1)
+    }
+    pub fn game(&self) -> Game { self.current.clone() }
+
+    pub fn step_forward(&mut self) -> bool {
+        if self.position >= self.file.actions.len() { return false; }
+        self.seek(self.position + 1);
+        true
+    }
+    pub fn step_back(&mut self) -> bool {
+        if self.position == 0 { return false; }
+        self.seek(self.position - 1);
+        true
+    }
+    pub fn next_turn(&mut self) -> bool {
+        let turn = self.turn();
+        self.jump_to_turn(turn + 1)
+    }
+    // Goes to the start of the current turn, or the one before if already there
+    pub fn previous_turn(&mut self) -> bool {
+        let turn = self.turn();
+        let at_turn_start = self.turn_starts.iter().any(|(index, _)| *index == self.position);
+        if at_turn_start { self.jump_to_turn(turn.saturating_sub(1)) } else { self.jump_to_turn(turn) }
+    }
+    pub fn jump_to_turn(&mut self, turn: u32) -> bool {
+        match self.turn_starts.iter().find(|(_, game)| game.turn_number() == turn) {
+            Some((index, _)) => {
+                let index = *index;
+                self.seek(index);
+                true
+            }
+            None => false,
+        }
+    }
+    pub fn seek(&mut self, position: usize) -> () {
+        let position = std::cmp::min(position, self.file.actions.len());
+        let (start, game) = self.turn_starts.iter().rev()
+            .find(|(index, _)| *index <= position)
+            .expect("the first turn starts at 0");
+        let mut game = game.clone();
+        game.history = History::replayed(&self.file.actions[..*start], &self.battles);
+        for action in &self.file.actions[*start..position] {
+            game.apply(action.clone()).expect("actions were checked when the replay was loaded");
+        }
+        self.current = game;
+        self.position = position;
+    }
+}
+
+impl Replay {
+    // Plays every action once up front, so a replay that loads can always be stepped through
+    pub fn new(file: ReplayFile) -> Result<Replay, ReplayError> {
+        if file.version != REPLAY_VERSION { return Err(ReplayError::UnsupportedVersion(file.version)); }
+        let mut game = Game::with_config(file.config.clone()).map_err(ReplayError::Config)?;
+        let first = game.clone();
+        let mut turn_starts = vec!((0, game.clone()));
+        for (index, action) in file.actions.iter().enumerate() {
+            let turn = game.turn_number();
+            game.apply(action.clone()).map_err(|error| ReplayError::InvalidAction { index, error })?;
+            if game.turn_number() != turn {
+                let history = std::mem::take(&mut game.history);
+                turn_starts.push((index + 1, game.clone()));
+                game.history = history;
+            }
+        }
+        let battles = game.battles().to_vec();
+        Ok(Replay { file, turn_starts, battles, position: 0, current: first })
+    }
+
+    pub fn file(&self) -> &ReplayFile { &self.file }
+    pub fn current(&self) -> &Game { &self.current }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Game {
+    pub fn to_replay(&self) -> Result<Replay, ReplayError> {
+        Replay::new(self.replay_file())
+    }
+}
+
+impl Game {
+    pub fn replay_file(&self) -> ReplayFile {
+        ReplayFile { version: REPLAY_VERSION, config: self.config.clone(), actions: self.history().to_vec() }
+    }
+}
@@ -0,0 +1,53 @@
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// ChaCha20, the same generator StdRng uses, but one whose position can be saved and restored
+#[derive(Clone, Debug)]
+pub struct GameRng {
+    seed: u64,
+    rng: ChaCha20Rng,
+}
+
+impl GameRng {
+    pub fn seed_from_u64(seed: u64) -> GameRng {
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+        // A generator that hasn't produced anything yet underflows working out its position
+        rng.set_word_pos(0);
+        GameRng { seed, rng }
+    }
+    pub fn seed(&self) -> u64 { self.seed }
+    pub fn word_pos(&self) -> u128 { self.rng.get_word_pos() }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 { self.rng.next_u32() }
+    fn next_u64(&mut self) -> u64 { self.rng.next_u64() }
+    fn fill_bytes(&mut self, dest: &mut [u8]) { self.rng.fill_bytes(dest) }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> { self.rng.try_fill_bytes(dest) }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RngState {
+    seed: u64,
+    // Split in two since not every format handles u128
+    word_pos_high: u64,
+    word_pos_low: u64,
+}
+
+impl Serialize for GameRng {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let word_pos = self.word_pos();
+        RngState { seed: self.seed, word_pos_high: (word_pos >> 64) as u64, word_pos_low: word_pos as u64 }
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GameRng {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state = RngState::deserialize(deserializer)?;
+        let mut rng = GameRng::seed_from_u64(state.seed);
+        rng.rng.set_word_pos(((state.word_pos_high as u128) << 64) | state.word_pos_low as u128);
+        Ok(rng)
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::Game;
+
+// Bump whenever the game state changes. JSON saves from OLDEST_JSON_VERSION on still load, with
+// anything added since filled in by its serde default. Binary saves have no field names to go by,
+// so they only load at the current version.
+pub const SAVE_VERSION: u32 = 11;
+pub const OLDEST_JSON_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct SaveFileRef<'a> {
+    version: u32,
+    game: &'a Game,
+}
+
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+#[derive(Deserialize)]
+struct SaveFile {
+    // Already checked through SaveHeader
+    #[serde(rename = "version")]
+    _version: u32,
+    game: Game,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Json(serde_json::Error),
+    Binary(bincode::Error),
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Json(e) => write!(f, "invalid saved game: {}", e),
+            SaveError::Binary(e) => write!(f, "invalid saved game: {}", e),
+            SaveError::UnsupportedVersion(v) =>
+                write!(f, "saved game version {} is not supported (this build reads up to {})", v, SAVE_VERSION),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(e: serde_json::Error) -> Self { SaveError::Json(e) }
+}
+
+impl From<bincode::Error> for SaveError {
+    fn from(e: bincode::Error) -> Self { SaveError::Binary(e) }
+}
+
+#[cfg(feature = "wasm")]
+impl From<SaveError> for JsValue {
+    fn from(e: SaveError) -> Self { JsValue::from_str(&e.to_string()) }
+}
+
+fn check_version(version: u32, oldest: u32) -> Result<(), SaveError> {
+    if (oldest..=SAVE_VERSION).contains(&version) { Ok(()) } else { Err(SaveError::UnsupportedVersion(version)) }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Game {
+    pub fn to_json(&self) -> Result<String, SaveError> {
+        Ok(serde_json::to_string(&SaveFileRef { version: SAVE_VERSION, game: self })?)
+    }
+    pub fn from_json(json: &str) -> Result<Game, SaveError> {
+        let header: SaveHeader = serde_json::from_str(json)?;
+        check_version(header.version, OLDEST_JSON_VERSION)?;
+        let mut save: SaveFile = serde_json::from_str(json)?;
+        // The earliest version 1 saves have no player names
+        for player in save.game.players.iter_mut().filter(|p| p.name.is_empty()) {
+            player.name = format!("Player {}", player.index + 1);
+        }
+        Ok(save.game)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SaveError> {
+        Ok(bincode::serialize(&SaveFileRef { version: SAVE_VERSION, game: self })?)
+    }
+    pub fn from_bytes(bytes: &[u8]) -> Result<Game, SaveError> {
+        // The version leads the encoding, so it can be checked before decoding the rest
+        let header: SaveHeader = bincode::deserialize(bytes)?;
+        check_version(header.version, SAVE_VERSION)?;
+        let save: SaveFile = bincode::deserialize(bytes)?;
+        Ok(save.game)
+    }
+}
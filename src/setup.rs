@@ -0,0 +1,139 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{Game, TurnPhase};
+use crate::action::{Action, GameError, Outcome};
+
+// How territories are shared out before the first turn
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum TerritoryDeal {
+    // Dealt out at random, round robin
+    #[default]
+    Random = 0,
+    // Players take turns claiming an unowned territory until none are left
+    Draft = 1,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SetupRules {
+    pub deal: TerritoryDeal,
+    // Troops each player starts with. Every territory a player gets takes one of them and the rest are
+    // deployed in turns before the first real turn. None keeps the map's troop counts and skips deploying.
+    pub army: Option<u32>,
+    // Most troops placed on each deployment turn, 0 counts as 1
+    pub troops_per_deploy: u32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Game {
+    pub fn is_setup_phase(&self) -> bool {
+        matches!(self.turn.phase, TurnPhase::Claim | TurnPhase::Deploy)
+    }
+    pub fn territory_deal(&self) -> TerritoryDeal { self.config.setup_rules.deal }
+    pub fn unclaimed_territories(&self) -> Vec<u32> {
+        (0..self.map.territories.len())
+            .filter(|t| self.territory_owner(*t).is_none() && !self.map.territories[*t].starts_neutral())
+            .map(|t| t as u32)
+            .collect()
+    }
+    // Starting troops the player still has to deploy
+    pub fn troops_to_deploy(&self, player: usize) -> u32 { self.players[player].reserve }
+    // Troops the on player places on their next deployment
+    pub fn deploy_size(&self) -> u32 {
+        std::cmp::min(self.config.setup_rules.troops_per_deploy.max(1), self.on_player().reserve)
+    }
+
+    pub fn claim(&mut self, territory: usize) -> Result<(), GameError> {
+        self.apply(Action::Claim { territory }).map(|_| ())
+    }
+    pub fn deploy(&mut self, territory: usize, troops: u32) -> Result<(), GameError> {
+        self.apply(Action::Deploy { territory, troops }).map(|_| ())
+    }
+}
+
+impl Game {
+    // Deals the territories or opens the draft, called once when the game is built
+    pub(crate) fn begin_setup(&mut self) -> () {
+        self.mark_neutrals();
+        self.deal_neutral_army();
+        if let Some(army) = self.config.setup_rules.army {
+            self.map.territories.iter_mut().for_each(|t| t.troops = 0);
+            self.players.iter_mut().for_each(|p| p.reserve = army);
+        }
+        self.garrison_neutrals();
+        match self.config.setup_rules.deal {
+            TerritoryDeal::Random => {
+                self.assign_territories();
+                for player in 0..self.players.len() {
+                    for territory in self.players[player].territories.clone() {
+                        self.garrison(player, territory as usize);
+                    }
+                }
+                self.begin_deployment(0);
+            },
+            TerritoryDeal::Draft => self.turn.phase = TurnPhase::Claim,
+        }
+    }
+
+    // A newly owned territory gets its first troop from the player's army
+    fn garrison(&mut self, player: usize, territory: usize) -> () {
+        if self.config.setup_rules.army.is_none() { return; }
+        let troops = std::cmp::min(1, self.players[player].reserve);
+        self.map.territories[territory].troops += troops;
+        self.players[player].reserve -= troops;
+    }
+
+    // Next player from `first` on with troops left to deploy
+    fn next_deployer(&self, first: usize) -> Option<usize> {
+        let count = self.players.len();
+        (0..count).map(|i| (first + i) % count).find(|p| self.players[*p].reserve > 0)
+    }
+
+    fn begin_deployment(&mut self, first: usize) -> () {
+        match self.next_deployer(first) {
+            Some(player) => {
+                self.turn.player_index = player as u32;
+                self.turn.phase = TurnPhase::Deploy;
+            },
+            None => self.begin_first_turn(),
+        }
+    }
+
+    fn begin_first_turn(&mut self) -> () {
+        self.turn.player_index = 0;
+        self.turn.phase = TurnPhase::Place;
+        self.turn.new_troops = self.calc_troop_bonus() as u32;
+    }
+
+    pub(crate) fn apply_claim(&mut self, territory: usize) -> Result<Outcome, GameError> {
+        self.check_phase(TurnPhase::Claim)?;
+        if territory >= self.map.territories.len() { return Err(GameError::UnknownTerritory); }
+        if !self.unclaimed_territories().contains(&(territory as u32)) { return Err(GameError::AlreadyOwned); }
+        let player = self.on_player_index();
+        self.players[player].capture_territory(territory as u32);
+        self.garrison(player, territory);
+        self.update_colors();
+        let next = (player + 1) % self.players.len();
+        if self.unclaimed_territories().is_empty() {
+            self.begin_deployment(next);
+        } else {
+            self.turn.player_index = next as u32;
+        }
+        Ok(Outcome::Claimed { territory })
+    }
+
+    pub(crate) fn apply_deploy(&mut self, territory: usize, troops: u32) -> Result<Outcome, GameError> {
+        self.check_phase(TurnPhase::Deploy)?;
+        self.check_owned(territory)?;
+        if troops == 0 { return Err(GameError::NoTroops); }
+        if troops > self.deploy_size() { return Err(GameError::NotEnoughTroops); }
+        let player = self.on_player_index();
+        self.map.territories[territory].add_troops(troops);
+        self.players[player].reserve -= troops;
+        self.begin_deployment(player + 1);
+        Ok(Outcome::Placed { territory, troops })
+    }
+}
@@ -0,0 +1,118 @@
+extern crate rust_wars;
+
+mod common;
+
+use rust_wars::{Action, Game, GameError, Outcome, TurnPhase};
+
+use common::deal;
+
+#[test]
+fn placing_on_an_enemy_territory_is_rejected() {
+    let mut game = Game::new();
+    let enemy = (0..5).find(|t| game.territory_owner(*t) == Some(1)).unwrap();
+    assert_eq!(game.apply(Action::PlaceTroops { territory: enemy, troops: 1 }), Err(GameError::NotOwned));
+    assert_eq!(game.apply(Action::PlaceTroops { territory: 99, troops: 1 }), Err(GameError::UnknownTerritory));
+}
+
+#[test]
+fn placing_more_than_the_reinforcements_is_rejected() {
+    let mut game = Game::new();
+    let own = (0..5).find(|t| game.territory_owner(*t) == Some(0)).unwrap();
+    let troops = game.new_troops();
+    assert_eq!(game.apply(Action::PlaceTroops { territory: own, troops: troops + 1 }), Err(GameError::NotEnoughTroops));
+    assert_eq!(game.apply(Action::PlaceTroops { territory: own, troops }), Ok(Outcome::Placed { territory: own, troops }));
+    assert_eq!(game.new_troops(), 0);
+}
+
+#[test]
+fn attacks_are_validated() {
+    let mut game = Game::new();
+    deal(&mut game, &[2, 4]);
+
+    assert_eq!(game.apply(Action::Attack { from: 0, to: 2, dice: 3 }), Err(GameError::WrongPhase));
+    game.place_troops(0, game.new_troops()).unwrap();
+    game.apply(Action::EndPhase).unwrap();
+    assert_eq!(game.turn_phase(), TurnPhase::Attack);
+    assert_eq!(game.apply(Action::Attack { from: 0, to: 1, dice: 3 }), Err(GameError::OwnTerritory));
+    assert_eq!(game.apply(Action::Attack { from: 0, to: 4, dice: 3 }), Err(GameError::NotAdjacent));
+    assert_eq!(game.apply(Action::Attack { from: 0, to: 2, dice: 0 }), Err(GameError::NoTroops));
+    assert_eq!(game.apply(Action::Attack { from: 0, to: 2, dice: 500 }), Err(GameError::NotEnoughTroops));
+}
+
+#[test]
+fn capture_waits_for_the_advance() {
+    let mut game = Game::new();
+    deal(&mut game, &[2, 4]);
+    game.place_troops(0, game.new_troops()).unwrap();
+    game.apply(Action::EndPhase).unwrap();
+
+    match game.apply(Action::Attack { from: 0, to: 2, dice: 100 }) {
+        Ok(Outcome::Battle { captured: true, .. }) => (),
+        other => panic!("expected a capture, got {:?}", other),
+    }
+    assert_eq!(game.turn_phase(), TurnPhase::PostAttackFortify);
+    assert_eq!(game.apply(Action::EndTurn), Err(GameError::AdvancePending));
+    assert_eq!(game.apply(Action::Advance { troops: 10 }), Ok(Outcome::Advanced { troops: 10 }));
+    assert_eq!(game.turn_phase(), TurnPhase::Attack);
+    assert_eq!(game.apply(Action::Advance { troops: 1 }), Err(GameError::NoAdvancePending));
+}
+
+#[test]
+fn fortify_cannot_empty_a_territory() {
+    let mut game = Game::new();
+    deal(&mut game, &[4]);
+    game.place_troops(0, game.new_troops()).unwrap();
+    game.apply(Action::EndPhase).unwrap();
+    game.apply(Action::EndPhase).unwrap();
+
+    assert_eq!(game.apply(Action::Fortify { from: 3, to: 0, troops: 4 }), Err(GameError::NotEnoughTroops));
+    assert_eq!(game.apply(Action::Fortify { from: 3, to: 0, troops: 3 }), Ok(Outcome::Fortified { troops: 3 }));
+}
+
+#[test]
+fn reinforcements_must_be_placed_before_attacking_or_ending_the_turn() {
+    let mut game = Game::new();
+    assert_eq!(game.attack_phase(), Err(GameError::UnplacedTroops));
+    assert_eq!(game.apply(Action::EndTurn), Err(GameError::UnplacedTroops));
+    let own = (0..5).find(|t| game.territory_owner(*t) == Some(0)).unwrap();
+    game.place_troops(own, game.new_troops()).unwrap();
+    assert_eq!(game.attack_phase(), Ok(()));
+}
+
+#[test]
+fn placement_cannot_resume_after_attacking() {
+    let mut game = Game::new();
+    let own = (0..5).find(|t| game.territory_owner(*t) == Some(0)).unwrap();
+    game.place_troops(own, game.new_troops()).unwrap();
+    game.attack_phase().unwrap();
+    assert_eq!(game.place_phase(), Err(GameError::IllegalPhaseChange));
+    game.fortify_phase().unwrap();
+    assert_eq!(game.attack_phase(), Err(GameError::IllegalPhaseChange));
+}
+
+#[test]
+fn single_fortification_ends_the_turn() {
+    let mut game = Game::new();
+    deal(&mut game, &[4]);
+    game.place_troops(0, game.new_troops()).unwrap();
+    game.end_phase().unwrap();
+    game.end_phase().unwrap();
+    game.fortify(0, 1, 1).unwrap();
+    assert_eq!(game.on_player_index(), 1);
+    assert_eq!(game.turn_phase(), TurnPhase::Place);
+    assert_eq!(game.turn_number(), 2);
+}
+
+#[test]
+fn staged_placements_are_all_placed_or_none_are() {
+    let mut game = Game::new();
+    deal(&mut game, &[2, 4]);
+    game.map_click_action(0).unwrap();
+    game.map_click_action(3).unwrap();
+    let troops = game.get_map().troops();
+    // 3 changes hands before the placements go in, so 0 can't be placed on either
+    deal(&mut game, &[2, 3, 4]);
+    assert_eq!(game.commit_placement_cache(), Err(GameError::NotOwned));
+    assert_eq!(game.get_map().troops(), troops);
+    assert_eq!(game.troops_staged_for_placement(), 2);
+}
@@ -0,0 +1,58 @@
+extern crate rust_wars;
+
+use rust_wars::{Bot, Game, GameConfig, TurnPhase};
+use rust_wars::ai::{GreedyBot, RandomBot, TurtleBot};
+
+fn bot_game(seed: u64, bots: &[Bot]) -> Game {
+    let config = bots.iter().enumerate()
+        .fold(GameConfig::new().seed(seed).player_count(bots.len()).starting_troops(3), |c, (i, bot)| c.player_bot(i, *bot));
+    Game::with_config(config).unwrap()
+}
+
+#[test]
+fn every_legal_action_applies() {
+    let mut game = bot_game(4, &[Bot::Random, Bot::Random]);
+    let mut bot = RandomBot::new(4);
+    for _ in 0..200 {
+        if game.is_over() { break; }
+        for action in game.legal_actions() {
+            game.clone().apply(action.clone()).unwrap_or_else(|e| panic!("{:?}: {}", action, e));
+        }
+        game.play_agent_action(&mut bot).unwrap();
+    }
+}
+
+#[test]
+fn marked_players_are_played_by_bots() {
+    let mut game = Game::with_config(GameConfig::new().starting_troops(3).player_bot(1, Bot::Turtle)).unwrap();
+    assert!(!game.is_ai_turn());
+    assert!(game.play_ai_turn().is_err());
+    assert!(game.player_bot(1) == Some(Bot::Turtle));
+
+    game.set_player_bot(0, Some(Bot::Greedy));
+    game.play_ai_turn().unwrap();
+    assert_eq!(game.on_player_index(), 1);
+    assert!(game.is_ai_turn());
+    game.play_ai_turn().unwrap();
+    assert_eq!(game.on_player_index(), 0);
+    assert_eq!(game.turn_phase(), TurnPhase::Place);
+}
+
+#[test]
+fn bots_play_a_game_to_the_end() {
+    for (seed, bots) in [(1, [Bot::Greedy, Bot::Turtle]), (2, [Bot::Random, Bot::Greedy]), (3, [Bot::Turtle, Bot::Random])] {
+        let mut game = bot_game(seed, &bots);
+        while !game.is_over() && game.turn_number() < 500 {
+            game.play_ai_turn().unwrap();
+        }
+        assert!(game.is_over(), "{:?} still going after 500 turns", bots);
+    }
+}
+
+#[test]
+fn bots_can_be_driven_directly() {
+    let mut game = Game::with_config(GameConfig::new().starting_troops(3)).unwrap();
+    game.play_agent_turn(&mut GreedyBot).unwrap();
+    game.play_agent_turn(&mut TurtleBot).unwrap();
+    assert_eq!(game.turn_number(), 3);
+}
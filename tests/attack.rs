@@ -0,0 +1,67 @@
+extern crate rust_wars;
+
+mod common;
+
+use rust_wars::{Action, AttackMode, GameError, Outcome};
+
+use common::attacking_game;
+
+#[test]
+fn single_roll_uses_one_exchange() {
+    let mut game = attacking_game(1);
+    let troops = game.get_map().troops()[0];
+    let log = game.attack_single_roll(0, 2).unwrap();
+    assert_eq!(log.rolls().len(), 1);
+    let roll = &log.rolls()[0];
+    assert_eq!((roll.attack_dice, roll.defense_dice), (3, 2));
+    assert_eq!(roll.attacker_losses + roll.defender_losses, 2);
+    assert_eq!(game.get_map().troops()[0], troops - roll.attacker_losses);
+    assert_eq!(game.get_map().troops()[2], 10 - roll.defender_losses);
+}
+
+#[test]
+fn stops_with_troops_left() {
+    for seed in 0..10 {
+        let mut game = attacking_game(seed);
+        let committed = game.get_map().troops()[0] - 15;
+        let log = game.attack_until_troops_left(0, 2, 15).unwrap();
+        let last = log.rolls().last().cloned().unwrap();
+        assert_eq!(log.attacker_losses() + last.attackers_left, committed);
+        if !log.captured {
+            assert_eq!(last.attackers_left, 0);
+            assert_eq!(game.get_map().troops()[0], 15);
+        }
+    }
+    let mut game = attacking_game(0);
+    assert_eq!(game.attack_until_troops_left(0, 2, 30).unwrap_err(), GameError::NoTroops);
+}
+
+#[test]
+fn stops_when_the_odds_drop() {
+    let mut game = attacking_game(2);
+    let log = game.attack_until_odds_below(0, 2, 50).unwrap();
+    let last = log.rolls().last().cloned().unwrap();
+    if !log.captured {
+        assert!(rust_wars::battle_odds(last.attackers_left, last.defenders_left).attacker_win() < 0.5);
+    }
+    // Odds already too low means no dice are rolled
+    let mut game = attacking_game(2);
+    game.set_troops(&0, &2);
+    assert!(game.attack_until_odds_below(0, 2, 50).unwrap().rolls().is_empty());
+}
+
+#[test]
+fn blitz_matches_attacking_with_everything() {
+    let mut blitz = attacking_game(3);
+    let mut all_in = attacking_game(3);
+    let log = blitz.blitz(0, 2).unwrap();
+    match all_in.apply(Action::Attack { from: 0, to: 2, dice: 25 }).unwrap() {
+        Outcome::Battle { rolls, captured, .. } => {
+            assert_eq!(rolls, log.rolls());
+            assert_eq!(captured, log.captured);
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    assert_eq!(blitz.get_map().troops(), all_in.get_map().troops());
+    assert!(matches!(blitz.history().last(), Some(Action::AttackUntil { mode: AttackMode::Blitz, .. })));
+}
@@ -0,0 +1,62 @@
+extern crate rust_wars;
+
+use rust_wars::{Action, BorderKind, Game, GameConfig, GameError, Map};
+
+// 0 and 1 share a normal border, 1 has artillery on 2, 0 and 3 are joined by a road troops can only
+// march along, and 2 and 3 share a normal border
+const ROADS: &str = r#"{
+    "width": 4, "height": 4,
+    "territories": [
+        { "polygon": [0, 1, 5, 0], "center": 5, "neighbors": [1], "borders": [{ "to": 3, "kind": "FortifyOnly" }] },
+        { "polygon": [1, 2, 6, 1], "center": 6, "neighbors": [0], "borders": [{ "to": 2, "kind": "AttackOnly" }] },
+        { "polygon": [5, 6, 10, 5], "center": 10, "neighbors": [3], "borders": [{ "to": 1, "kind": "DefendOnly" }] },
+        { "polygon": [9, 10, 13, 9], "center": 13, "neighbors": [2], "borders": [{ "to": 0, "kind": "FortifyOnly" }] }
+    ],
+    "countries": [
+        { "name": "Top", "territories": [0, 1], "bonus": 2 },
+        { "name": "Bottom", "territories": [2, 3], "bonus": 2 }
+    ]
+}"#;
+
+fn game(owned: &[u32]) -> Game {
+    let mut game = Game::with_config(GameConfig::new().starting_troops(3).map(Map::from_json(ROADS).unwrap())).unwrap();
+    for t in 0..4 { game.assign_territory(t, if owned.contains(&t) { 0 } else { 1 }); }
+    game.place_troops(owned[0] as usize, game.new_troops()).unwrap();
+    game
+}
+
+#[test]
+fn borders_keep_their_kind() {
+    let map = Map::from_json(ROADS).unwrap();
+    assert!(map.validate().is_empty());
+    assert_eq!(map.border_kind(1, 2), Some(BorderKind::AttackOnly));
+    assert_eq!(map.border_kind(1, 3), None);
+    assert!(map.can_attack(1, 2) && !map.can_move(1, 2));
+    assert!(!map.can_attack(2, 1) && !map.can_move(2, 1));
+    assert!(!map.can_attack(0, 3) && map.can_move(0, 3));
+    assert_eq!(map.neighbors(2), vec![3, 1]);
+    assert_eq!(map.attack_targets(2), vec![3]);
+    assert_eq!(Map::from_json(&map.to_json().unwrap()).unwrap().to_json().unwrap(), map.to_json().unwrap());
+}
+
+#[test]
+fn attacks_follow_attacking_borders() {
+    let mut game = game(&[0, 1]);
+    game.attack_phase().unwrap();
+    game.map_click_action(0).unwrap();
+    assert!(!game.get_map().is_highlighted(3));
+    assert_eq!(game.apply(Action::Attack { from: 0, to: 3, dice: 2 }), Err(GameError::NotAdjacent));
+    game.map_click_action(0).unwrap();
+    game.map_click_action(1).unwrap();
+    assert!(game.get_map().is_highlighted(2));
+}
+
+#[test]
+fn fortifying_follows_moving_borders() {
+    let mut game = game(&[1, 2, 3]);
+    game.fortify_phase().unwrap();
+    assert!(game.fortify_targets(1).is_empty());
+    assert_eq!(game.fortify_targets(2), vec![3]);
+    assert_eq!(game.apply(Action::Fortify { from: 1, to: 2, troops: 1 }), Err(GameError::NotAdjacent));
+    assert!(game.apply(Action::Fortify { from: 3, to: 2, troops: 1 }).is_ok());
+}
@@ -0,0 +1,57 @@
+extern crate rust_wars;
+
+use rust_wars::{find_set, Card, CardRules, CardSymbol, Game, GameError, TradeSchedule};
+
+#[test]
+fn trading_a_set_adds_troops_during_placement() {
+    let mut game = Game::new();
+    while !game.has_card_set() { game.award_card(0); }
+    let troops_before = game.new_troops();
+    let set = find_set(&game.hand()).unwrap();
+    let hand_size = game.hand_size();
+
+    assert_eq!(game.trade_cards(set), Ok(4));
+    assert_eq!(game.new_troops(), troops_before + 4);
+    assert_eq!(game.hand_size(), hand_size - 3);
+    assert_eq!(game.cards_traded(), 1);
+}
+
+#[test]
+fn cards_cannot_be_traded_outside_placement() {
+    let mut game = Game::new();
+    let own = (0..5).find(|t| game.territory_owner(*t) == Some(0)).unwrap();
+    game.place_troops(own, game.new_troops()).unwrap();
+    game.attack_phase().unwrap();
+    while !game.has_card_set() { game.award_card(0); }
+    assert_eq!(game.trade_cards(find_set(&game.hand()).unwrap()), Err(GameError::WrongPhase));
+}
+
+#[test]
+fn five_cards_force_a_trade_before_attacking() {
+    let mut game = Game::new();
+    (0..5).for_each(|_| game.award_card(0));
+    assert!(game.must_trade_cards());
+    assert_eq!(game.attack_phase(), Err(GameError::MustTradeCards));
+    assert!(game.is_place_phase());
+}
+
+#[test]
+fn trade_schedules_escalate() {
+    let increasing = CardRules::default();
+    let capped = CardRules { schedule: TradeSchedule::Capped(10), ..CardRules::default() };
+    let values: Vec<u32> = (0..8).map(|n| increasing.trade_value(n, &[])).collect();
+    assert_eq!(values, vec![4, 6, 8, 10, 12, 15, 20, 25]);
+    assert_eq!(capped.trade_value(7, &[]), 10);
+}
+
+#[test]
+fn fixed_sets_with_wilds_take_their_best_value() {
+    let fixed = CardRules { schedule: TradeSchedule::Fixed, ..CardRules::default() };
+    let value = |symbols: [CardSymbol; 3]| fixed.trade_value(0, &symbols.map(|s| Card::new(None, s)));
+    use CardSymbol::*;
+    assert_eq!(value([Cavalry, Cavalry, Cavalry]), 6);
+    assert_eq!(value([Cavalry, Cavalry, Wild]), 6);
+    assert_eq!(value([Infantry, Wild, Wild]), 10);
+    assert_eq!(value([Infantry, Artillery, Wild]), 10);
+    assert_eq!(value([Wild, Wild, Wild]), 10);
+}
@@ -0,0 +1,20 @@
+// Setups shared by the integration tests, each test file only uses some of them
+#![allow(dead_code)]
+
+use rust_wars::{Game, GameConfig};
+
+// Player 1 holds `theirs` and player 0 the rest of the built-in map
+pub fn deal(game: &mut Game, theirs: &[u32]) {
+    for t in 0..5 { game.assign_territory(t, if theirs.contains(&t) { 1 } else { 0 }); }
+}
+
+// Player 0 attacking, with 20 troops on 0 facing 10 on player 1's 2, player 1 also holds 4
+pub fn attacking_game(seed: u64) -> Game {
+    let mut game = Game::with_config(GameConfig::new().seed(seed).starting_troops(3)).unwrap();
+    deal(&mut game, &[2, 4]);
+    game.set_troops(&0, &20);
+    game.set_troops(&2, &10);
+    game.place_troops(0, game.new_troops()).unwrap();
+    game.attack_phase().unwrap();
+    game
+}
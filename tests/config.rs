@@ -0,0 +1,54 @@
+extern crate rust_wars;
+
+use rust_wars::{ConfigError, Game, GameConfig};
+
+fn owners(game: &Game) -> Vec<Option<u32>> {
+    (0..5).map(|t| game.territory_owner(t)).collect()
+}
+
+#[test]
+fn new_is_the_default_preset() {
+    let preset = Game::new();
+    let configured = Game::with_config(GameConfig::default()).unwrap();
+    assert_eq!(preset.to_json().unwrap(), configured.to_json().unwrap());
+}
+
+#[test]
+fn seeds_change_the_deal() {
+    let deals: Vec<Vec<Option<u32>>> = (0..10)
+        .map(|seed| owners(&Game::with_config(GameConfig::new().seed(seed)).unwrap()))
+        .collect();
+    assert!(deals.iter().any(|d| *d != deals[0]));
+    assert_eq!(owners(&Game::with_config(GameConfig::new().seed(7)).unwrap()), deals[7]);
+}
+
+#[test]
+fn players_are_named_and_colored() {
+    let config = GameConfig::new().player_count(4).player_name(2, String::from("Dan")).player_color(3, 0x123456);
+    let game = Game::with_config(config).unwrap();
+    assert_eq!(game.player_count(), 4);
+    assert_eq!(game.player_name(0), "Player 1");
+    assert_eq!(game.player_name(2), "Dan");
+    assert_eq!(game.player_color(3), 0x123456);
+    assert!((0..4).all(|p| owners(&game).contains(&Some(p))));
+}
+
+#[test]
+fn player_count_is_limited() {
+    assert_eq!(Game::with_config(GameConfig::new().player_count(1)).err(), Some(ConfigError::TooFewPlayers));
+    assert_eq!(Game::with_config(GameConfig::new().player_count(9)).err(), Some(ConfigError::TooManyPlayers));
+    assert_eq!(Game::with_config(GameConfig::new().player_count(6)).err(), Some(ConfigError::NotEnoughTerritories));
+}
+
+#[test]
+fn starting_troops_replace_the_map_defaults() {
+    let game = Game::with_config(GameConfig::new().starting_troops(2)).unwrap();
+    assert_eq!(game.get_map().troops(), vec![2; 5]);
+}
+
+#[test]
+fn configs_round_trip_through_json() {
+    let config = GameConfig::new().seed(9).player_count(3);
+    let json = config.to_json().unwrap();
+    assert_eq!(GameConfig::from_json(&json).unwrap().to_json().unwrap(), json);
+}
@@ -0,0 +1,131 @@
+extern crate rust_wars;
+
+mod common;
+
+use rust_wars::{battle_odds, Game, GameConfig, GameError, TerritoryDeal};
+
+use common::{attacking_game, deal};
+
+#[test]
+fn faces_explain_the_losses() {
+    let mut game = attacking_game(1);
+    let log = game.blitz(0, 2).unwrap();
+    for roll in log.rolls() {
+        let (attack, defense) = (roll.attack_faces(), roll.defense_faces());
+        assert_eq!(attack.len() as u32, roll.attack_dice);
+        assert_eq!(defense.len() as u32, roll.defense_dice);
+        assert!(attack.windows(2).all(|w| w[0] >= w[1]) && defense.windows(2).all(|w| w[0] >= w[1]));
+        assert!(attack.iter().chain(defense.iter()).all(|f| (1..=6).contains(f)));
+        let wins = attack.iter().zip(defense.iter()).filter(|(a, d)| a > d).count() as u32;
+        assert_eq!(wins, roll.defender_losses);
+    }
+}
+
+#[test]
+fn battles_are_kept_in_the_history() {
+    let mut game = attacking_game(2);
+    assert_eq!(game.battle_count(), 0);
+    let first = game.attack_single_roll(0, 2).unwrap();
+    let second = game.attack_single_roll(0, 2).unwrap();
+    assert_eq!(game.battle_count(), 2);
+    assert_eq!(game.battle(0), Some(first.clone()));
+    assert_eq!(game.last_battle(), Some(second));
+    assert_eq!((first.from, first.to, first.player, first.turn), (0, 2, 0, 1));
+    assert_eq!(first.action, game.action_count() - 2);
+
+    let loaded = Game::from_json(&game.to_json().unwrap()).unwrap();
+    assert_eq!(loaded.battles(), game.battles());
+}
+
+fn rules_game(config: GameConfig) -> Game {
+    let mut game = Game::with_config(config.starting_troops(3)).unwrap();
+    deal(&mut game, &[2, 4]);
+    game.set_troops(&0, &30);
+    game.set_troops(&2, &20);
+    game
+}
+
+#[test]
+fn dice_rules_change_the_rolls() {
+    let mut game = rules_game(GameConfig::new().die_faces(10).max_dice(2, 1));
+    game.place_troops(0, game.new_troops()).unwrap();
+    game.attack_phase().unwrap();
+    for roll in game.blitz(0, 2).unwrap().rolls() {
+        assert!(roll.attack_dice <= 2 && roll.defense_dice == 1);
+        assert!(roll.attack_faces().iter().all(|f| (1..=10).contains(f)));
+    }
+}
+
+#[test]
+fn ties_to_attacker_improve_the_odds() {
+    let classic = rules_game(GameConfig::new());
+    let ties = rules_game(GameConfig::new().ties_to_attacker(true));
+    assert!(ties.attack_odds(0, 2).unwrap().attacker_win() > classic.attack_odds(0, 2).unwrap().attacker_win());
+    let mut game = rules_game(GameConfig::new().ties_to_attacker(true));
+    game.set_troops(&0, &2);
+    game.set_troops(&2, &1);
+    let odds = game.attack_odds(0, 2).unwrap();
+    assert!((odds.attacker_win() - 21.0 / 36.0).abs() < 1e-9);
+    // Holding troops back follows the same rules
+    game.set_troops(&0, &9);
+    assert_eq!(game.attack_odds_with(0, 2, 1).unwrap(), odds);
+}
+
+#[test]
+fn players_choose_their_dice() {
+    let mut game = rules_game(GameConfig::new());
+    assert_eq!(game.choose_dice(4, 2), Err(GameError::InvalidDice));
+    assert_eq!(game.choose_dice(1, 0), Err(GameError::InvalidDice));
+    game.choose_dice(1, 1).unwrap();
+    assert_eq!((game.attack_dice_for(0), game.defense_dice_for(0)), (1, 1));
+    assert_eq!(game.attack_dice_for(1), 3);
+    game.place_troops(0, game.new_troops()).unwrap();
+    game.attack_phase().unwrap();
+    let roll = game.attack_single_roll(0, 2).unwrap().rolls()[0].clone();
+    assert_eq!((roll.attack_dice, roll.defense_dice), (1, 2));
+    assert_eq!(roll.attacker_losses + roll.defender_losses, 1);
+
+    // Only while placing or attacking
+    game.fortify_phase().unwrap();
+    assert_eq!(game.choose_dice(2, 2), Err(GameError::WrongPhase));
+    let mut draft = Game::with_config(GameConfig::new().territory_deal(TerritoryDeal::Draft)).unwrap();
+    assert_eq!(draft.choose_dice(2, 2), Err(GameError::WrongPhase));
+}
+
+#[test]
+fn balanced_blitz_settles_in_one_draw() {
+    let mut game = rules_game(GameConfig::new().balanced_blitz(true));
+    game.place_troops(0, game.new_troops()).unwrap();
+    game.attack_phase().unwrap();
+    let log = game.blitz(0, 2).unwrap();
+    assert_eq!(log.rolls().len(), 1);
+    let roll = &log.rolls()[0];
+    assert!(roll.attack_faces().is_empty());
+    assert!(roll.attackers_left == 0 || roll.defenders_left == 0);
+    assert_eq!(log.captured, roll.defenders_left == 0);
+    // Dice need at least two faces
+    assert!(Game::with_config(GameConfig::new().die_faces(1)).is_err());
+}
+
+#[test]
+fn balanced_blitz_keeps_the_true_chance_of_winning() {
+    let draws = 2000;
+    let mut captures = 0;
+    let mut odds = None;
+    for seed in 0..draws {
+        let mut game = Game::with_config(GameConfig::new().seed(seed).starting_troops(3).balanced_blitz(true)).unwrap();
+        deal(&mut game, &[2, 4]);
+        game.place_troops(0, game.new_troops()).unwrap();
+        game.attack_phase().unwrap();
+        // Reinforcements depend on the deal, so the fight is set up after placing
+        game.set_troops(&0, &8);
+        game.set_troops(&2, &6);
+        odds.get_or_insert_with(|| game.attack_odds(0, 2).unwrap());
+        if game.blitz(0, 2).unwrap().captured { captures += 1; }
+    }
+    let (balanced, rolled) = (odds.unwrap(), battle_odds(7, 6));
+    assert!((balanced.attacker_win() - rolled.attacker_win()).abs() < 1e-9);
+    assert_ne!(balanced.attacker_survivors(), rolled.attacker_survivors());
+    let win_rate = captures as f64 / draws as f64;
+    assert!((win_rate - rolled.attacker_win()).abs() < 0.04, "won {} against odds of {}", win_rate, rolled.attacker_win());
+}
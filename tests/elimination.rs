@@ -0,0 +1,52 @@
+extern crate rust_wars;
+
+mod common;
+
+use rust_wars::Game;
+
+use common::deal;
+
+fn game_with_last_territory_under_attack() -> Game {
+    let mut game = Game::new();
+    deal(&mut game, &[2]);
+    game.place_troops(0, game.new_troops()).unwrap();
+    game.attack_phase().unwrap();
+    game.map_click_action(0).unwrap();
+    game.map_click_action(2).unwrap();
+    game
+}
+
+#[test]
+fn capturing_the_last_territory_records_the_elimination() {
+    let mut game = game_with_last_territory_under_attack();
+    game.attack_all().unwrap();
+
+    assert!(game.is_over());
+    let eliminations = game.eliminations();
+    assert_eq!(eliminations.len(), 1);
+    assert_eq!(eliminations[0].player, 1);
+    assert_eq!(eliminations[0].eliminated_by, 0);
+    assert_eq!(eliminations[0].turn, 1);
+}
+
+#[test]
+fn eliminator_takes_the_eliminated_hand() {
+    let mut game = game_with_last_territory_under_attack();
+    game.award_card(1);
+    game.award_card(1);
+    game.attack_all().unwrap();
+
+    assert_eq!(game.hand_size(), 2);
+}
+
+#[test]
+fn oversized_captured_hand_is_traded_immediately() {
+    let mut game = game_with_last_territory_under_attack();
+    (0..6).for_each(|_| game.award_card(1));
+    let troops_before = game.new_troops();
+    game.attack_all().unwrap();
+
+    assert!(game.hand_size() < 5);
+    assert!(game.cards_traded() >= 1);
+    assert!(game.new_troops() > troops_before);
+}
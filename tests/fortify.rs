@@ -0,0 +1,116 @@
+extern crate rust_wars;
+
+mod common;
+
+use rust_wars::{Action, FortifyReach, Game, GameConfig, GameError};
+
+use common::deal;
+
+// Player 0 holds 0, 3 and 4, where 4 is cut off from the other two by player 1's 1 and 2
+fn fortify_game(reach: FortifyReach) -> Game {
+    let mut game = Game::with_config(GameConfig::new().starting_troops(5).fortify_reach(reach)).unwrap();
+    deal(&mut game, &[1, 2]);
+    game.place_troops(0, game.new_troops()).unwrap();
+    game.fortify_phase().unwrap();
+    game
+}
+
+#[test]
+fn targets_follow_the_reach_rule() {
+    assert_eq!(fortify_game(FortifyReach::Adjacent).fortify_targets(3), vec!(0));
+    assert_eq!(fortify_game(FortifyReach::Connected).fortify_targets(3), vec!(0));
+    let mut unlimited = fortify_game(FortifyReach::Unlimited).fortify_targets(3);
+    unlimited.sort_unstable();
+    assert_eq!(unlimited, vec!(0, 4));
+    assert!(fortify_game(FortifyReach::Adjacent).fortify_targets(1).is_empty());
+}
+
+#[test]
+fn connected_reach_follows_owned_chains() {
+    let mut game = Game::with_config(GameConfig::new().starting_troops(5).fortify_reach(FortifyReach::Connected)).unwrap();
+    deal(&mut game, &[2]);
+    // 3 -> 0 -> 1 -> 4
+    let mut targets = game.fortify_targets(3);
+    targets.sort_unstable();
+    assert_eq!(targets, vec!(0, 1, 4));
+    game.place_troops(0, game.new_troops()).unwrap();
+    game.fortify_phase().unwrap();
+    game.map_click_action(3).unwrap();
+    assert!(game.get_map().is_highlighted(4));
+    assert!(!game.get_map().is_highlighted(2));
+    game.apply(Action::Fortify { from: 3, to: 4, troops: 2 }).unwrap();
+}
+
+#[test]
+fn fortifying_out_of_reach_is_rejected() {
+    let mut game = fortify_game(FortifyReach::Connected);
+    assert_eq!(game.apply(Action::Fortify { from: 3, to: 4, troops: 1 }), Err(GameError::NotAdjacent));
+    let mut game = fortify_game(FortifyReach::Unlimited);
+    game.map_click_action(3).unwrap();
+    assert!(game.get_map().is_highlighted(4));
+    assert!(game.apply(Action::Fortify { from: 3, to: 4, troops: 1 }).is_ok());
+}
+
+#[test]
+fn legal_actions_follow_the_reach_rule() {
+    let offers = |game: Game| game.legal_actions().iter().any(|a| matches!(a, Action::Fortify { from: 3, to: 4, .. }));
+    assert!(!offers(fortify_game(FortifyReach::Adjacent)));
+    assert!(!offers(fortify_game(FortifyReach::Connected)));
+    assert!(offers(fortify_game(FortifyReach::Unlimited)));
+}
+
+#[test]
+fn moved_troops_stay_put_for_the_turn() {
+    let config = GameConfig::new().starting_troops(5).fortifications_per_turn(0);
+    let mut game = Game::with_config(config).unwrap();
+    deal(&mut game, &[2, 4]);
+    game.place_troops(0, game.new_troops()).unwrap();
+    game.fortify_phase().unwrap();
+    assert_eq!(game.fortifications_left(), None);
+
+    game.apply(Action::Fortify { from: 3, to: 0, troops: 4 }).unwrap();
+    assert_eq!(game.fortified_troops(0), 4);
+    // 0 now has its own troops plus the 4 that just arrived, only its own can go on
+    let own = game.get_map().troops()[0] - 4;
+    assert_eq!(game.apply(Action::Fortify { from: 0, to: 1, troops: own }), Err(GameError::AlreadyMoved));
+    game.apply(Action::Fortify { from: 0, to: 1, troops: own - 1 }).unwrap();
+    assert_eq!(game.fortifications_made(), 2);
+    // One of its own troops and the four newcomers are left, the newcomers can't be picked up
+    game.map_click_action(0).unwrap();
+    assert_eq!(game.get_map().troops()[0], 5);
+    assert_eq!(game.troops_available_for_movement(), 1);
+
+    game.end_turn().unwrap();
+    assert_eq!(game.fortified_troops(0), 0);
+}
+
+#[test]
+fn fortification_limit_ends_the_turn() {
+    let mut game = Game::with_config(GameConfig::new().starting_troops(5).fortifications_per_turn(2)).unwrap();
+    deal(&mut game, &[2, 4]);
+    game.place_troops(0, game.new_troops()).unwrap();
+    game.fortify_phase().unwrap();
+    assert_eq!(game.fortifications_left(), Some(2));
+    game.apply(Action::Fortify { from: 3, to: 0, troops: 1 }).unwrap();
+    assert_eq!(game.fortifications_left(), Some(1));
+    game.apply(Action::Fortify { from: 1, to: 0, troops: 1 }).unwrap();
+    assert_eq!(game.on_player_index(), 1);
+    assert_eq!(game.fortifications_made(), 0);
+}
+
+#[test]
+fn moved_troops_always_save_the_same_way() {
+    let fortified = || {
+        let mut game = Game::with_config(GameConfig::new().starting_troops(5).fortifications_per_turn(0)).unwrap();
+        deal(&mut game, &[2, 4]);
+        game.place_troops(0, game.new_troops()).unwrap();
+        game.fortify_phase().unwrap();
+        for (from, to) in [(0, 1), (0, 3), (1, 0)] {
+            game.apply(Action::Fortify { from, to, troops: 1 }).unwrap();
+        }
+        game
+    };
+    let game = fortified();
+    assert_eq!(fortified().to_bytes().unwrap(), game.to_bytes().unwrap());
+    assert_eq!(Game::from_json(&game.to_json().unwrap()).unwrap().to_json().unwrap(), game.to_json().unwrap());
+}
@@ -0,0 +1,53 @@
+extern crate rust_wars;
+
+use rust_wars::{Bot, Game, GameConfig, MapError, MapGenerator};
+
+#[test]
+fn generated_maps_are_sound() {
+    for seed in 0..20 {
+        let map = MapGenerator::new().seed(seed).generate().unwrap();
+        assert!(map.validate().is_empty(), "seed {}: {:?}", seed, map.validate());
+        assert_eq!(map.territory_count(), 24);
+        assert_eq!(map.country_count(), 5);
+        for c in 0..map.country_count() {
+            assert!(map.country_bonus(c) >= 1);
+        }
+    }
+    let small = MapGenerator::new().size(20, 12).territories(7).countries(9).generate().unwrap();
+    assert!(small.validate().is_empty());
+    assert_eq!(small.country_count(), 7);
+}
+
+#[test]
+fn the_seed_decides_the_map() {
+    let generator = MapGenerator::new().seed(7);
+    assert_eq!(generator.generate().unwrap().to_json().unwrap(), generator.generate().unwrap().to_json().unwrap());
+    assert_ne!(generator.generate().unwrap().to_json().unwrap(), generator.clone().seed(8).generate().unwrap().to_json().unwrap());
+}
+
+#[test]
+fn bigger_countries_are_worth_more() {
+    let map = MapGenerator::new().seed(3).territories(30).countries(2).generate().unwrap();
+    let lone = MapGenerator::new().seed(3).territories(30).countries(30).generate().unwrap();
+    assert!((0..map.country_count()).all(|c| map.country_bonus(c) > 1));
+    assert!((0..lone.country_count()).all(|c| lone.country_bonus(c) == 1));
+}
+
+#[test]
+fn crowded_grids_are_refused() {
+    assert!(matches!(MapGenerator::new().size(10, 10).territories(50).generate(), Err(MapError::NoRoom { territories: 50, .. })));
+    assert!(matches!(MapGenerator::new().territories(0).generate(), Err(MapError::NoTerritories)));
+}
+
+#[test]
+fn bots_finish_games_on_generated_maps() {
+    let map = MapGenerator::new().seed(11).territories(16).countries(4).generate().unwrap();
+    let config = GameConfig::new().seed(11).player_count(3).map(map)
+        .player_bot(0, Bot::Greedy).player_bot(1, Bot::Greedy).player_bot(2, Bot::Turtle);
+    let mut game = Game::with_config(config).unwrap();
+    for _ in 0..500 {
+        if game.is_over() { break; }
+        game.play_ai_turn().unwrap();
+    }
+    assert!(game.is_over());
+}
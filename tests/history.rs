@@ -0,0 +1,76 @@
+extern crate rust_wars;
+
+mod common;
+
+use rust_wars::{Action, Game, GameError, TurnPhase};
+
+use common::deal;
+
+fn game_with_front() -> Game {
+    let mut game = Game::new();
+    deal(&mut game, &[2, 4]);
+    game
+}
+
+#[test]
+fn placements_can_be_undone_and_redone() {
+    let mut game = game_with_front();
+    let troops = game.get_map().troops();
+    let reinforcements = game.new_troops();
+    game.apply(Action::PlaceTroops { territory: 0, troops: 2 }).unwrap();
+    game.apply(Action::PlaceTroops { territory: 1, troops: 1 }).unwrap();
+
+    game.undo().unwrap();
+    game.undo().unwrap();
+    assert_eq!(game.get_map().troops(), troops);
+    assert_eq!(game.new_troops(), reinforcements);
+    assert_eq!(game.undo(), Err(GameError::NothingToUndo));
+
+    game.redo().unwrap();
+    assert_eq!(game.get_map().troops()[0], troops[0] + 2);
+    assert_eq!(game.history(), &[Action::PlaceTroops { territory: 0, troops: 2 }]);
+    assert!(game.can_redo());
+}
+
+#[test]
+fn phase_changes_can_be_undone() {
+    let mut game = game_with_front();
+    game.place_troops(0, game.new_troops()).unwrap();
+    game.apply(Action::EndPhase).unwrap();
+    game.undo().unwrap();
+    assert_eq!(game.turn_phase(), TurnPhase::Place);
+}
+
+#[test]
+fn attacks_are_a_checkpoint() {
+    let mut game = game_with_front();
+    game.place_troops(0, game.new_troops()).unwrap();
+    game.apply(Action::EndPhase).unwrap();
+    game.apply(Action::Attack { from: 0, to: 2, dice: 3 }).unwrap();
+
+    assert!(!game.can_undo());
+    assert_eq!(game.undo(), Err(GameError::NothingToUndo));
+    assert_eq!(game.action_count(), 3);
+}
+
+#[test]
+fn the_end_of_a_turn_is_a_checkpoint() {
+    let mut game = game_with_front();
+    game.place_troops(0, game.new_troops()).unwrap();
+    game.apply(Action::EndPhase).unwrap();
+    game.apply(Action::EndTurn).unwrap();
+
+    assert_eq!(game.on_player_index(), 1);
+    assert!(!game.can_undo());
+    assert_eq!(game.undo(), Err(GameError::NothingToUndo));
+    assert_eq!(game.turn_phase(), TurnPhase::Place);
+}
+
+#[test]
+fn a_new_action_discards_the_redo_stack() {
+    let mut game = game_with_front();
+    game.apply(Action::PlaceTroops { territory: 0, troops: 2 }).unwrap();
+    game.undo().unwrap();
+    game.apply(Action::PlaceTroops { territory: 1, troops: 2 }).unwrap();
+    assert_eq!(game.redo(), Err(GameError::NothingToRedo));
+}
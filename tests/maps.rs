@@ -0,0 +1,84 @@
+extern crate rust_wars;
+
+use rust_wars::{Game, GameConfig, Map, MapError, MapProblem};
+
+const TRIANGLE: &str = r#"{
+    "name": "Triangle",
+    "width": 4, "height": 4,
+    "troops": 2,
+    "territories": [
+        { "name": "A", "polygon": [0, 1, 5, 0], "center": 5, "neighbors": [1, 2] },
+        { "name": "B", "polygon": [1, 2, 6, 1], "center": 6, "neighbors": [0, 2], "troops": 6 },
+        { "name": "C", "polygon": [5, 6, 10, 5], "center": 10, "neighbors": [0, 1], "neutral": 4 }
+    ],
+    "countries": [
+        { "name": "Top", "territories": [0, 1], "bonus": 2, "color": 255 },
+        { "name": "Bottom", "territories": [2], "bonus": 1 }
+    ]
+}"#;
+
+#[test]
+fn the_bundled_map_is_the_built_in_one() {
+    let map = Map::from_json(include_str!("../maps/tiny.json")).unwrap();
+    assert_eq!(map.to_json().unwrap(), Map::new().to_json().unwrap());
+    assert_eq!(map.name(), "Tiny");
+}
+
+#[test]
+fn map_files_fill_in_defaults() {
+    let map: Map = TRIANGLE.parse().unwrap();
+    assert_eq!(map.territory_count(), 3);
+    assert_eq!(map.territory_name(1), "B");
+    assert_eq!(map.troops(), vec![2, 6, 2]);
+    assert_eq!(map.neighbors(2), vec![0, 1]);
+    assert_eq!(map.color_for(0), 255);
+    assert_eq!(map.country_bonus(1), 1);
+    assert_eq!(Map::from_json(&map.to_json().unwrap()).unwrap().to_json().unwrap(), map.to_json().unwrap());
+
+    let game = Game::with_config(GameConfig::new().map(map)).unwrap();
+    assert!(game.is_neutral(2));
+    assert_eq!(game.get_map().troops()[2], 4);
+}
+
+#[test]
+fn broken_map_files_are_rejected() {
+    let unknown_neighbor = TRIANGLE.replace("\"neighbors\": [0, 1]", "\"neighbors\": [0, 3]");
+    assert!(matches!(Map::from_json(&unknown_neighbor), Err(MapError::UnknownNeighbor { territory: 2, neighbor: 3 })));
+    let off_grid = TRIANGLE.replace("\"center\": 10", "\"center\": 16");
+    assert!(matches!(Map::from_json(&off_grid), Err(MapError::OffGrid { territory: 2, point: 16 })));
+    let unknown_territory = TRIANGLE.replace("[2], \"bonus\": 1", "[5], \"bonus\": 1");
+    assert!(matches!(Map::from_json(&unknown_territory), Err(MapError::UnknownTerritory { country: 1, territory: 5 })));
+    assert!(matches!(Map::from_json("{ \"width\": 4 }"), Err(MapError::Json(_))));
+    let too_large = TRIANGLE.replace("\"width\": 4, \"height\": 4", "\"width\": 65536, \"height\": 65536");
+    assert!(matches!(Map::from_json(&too_large), Err(MapError::TooLarge { width: 65536, height: 65536 })));
+}
+
+#[test]
+fn validate_finds_what_is_wrong() {
+    assert_eq!(Map::new().validate(), vec![MapProblem::NoCountry { territory: 4 }]);
+    assert!(Map::from_json(TRIANGLE).unwrap().validate().is_empty());
+
+    let broken = r#"{
+        "width": 4, "height": 4,
+        "territories": [
+            { "polygon": [0, 1, 5, 0], "center": 5, "neighbors": [1, 0] },
+            { "polygon": [1, 2, 6], "center": 6, "neighbors": [] },
+            { "polygon": [5, 6, 10, 5], "center": 10 }
+        ],
+        "countries": [
+            { "name": "Top", "territories": [0, 1] },
+            { "name": "Also top", "territories": [1, 2] },
+            { "name": "Nothing", "territories": [] }
+        ]
+    }"#;
+    let map = Map::from_json(broken).unwrap();
+    assert_eq!(map.validate(), vec![
+        MapProblem::OneSidedBorder { territory: 0, neighbor: 1 },
+        MapProblem::BordersItself { territory: 0 },
+        MapProblem::OpenPolygon { territory: 1 },
+        MapProblem::SeveralCountries { territory: 1, countries: vec![0, 1] },
+        MapProblem::Disconnected { territories: vec![2] },
+        MapProblem::EmptyCountry { country: 2 },
+    ]);
+    assert_eq!(map.describe_problems()[0], "territory 0 borders territory 1, but not the other way around");
+}
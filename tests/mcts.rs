@@ -0,0 +1,69 @@
+extern crate rust_wars;
+
+mod common;
+
+use rust_wars::{Action, Bot, Game, GameConfig};
+use rust_wars::ai::{Agent, MctsBot};
+
+use common::deal;
+
+fn small_game(seed: u64) -> Game {
+    Game::with_config(GameConfig::new().seed(seed).starting_troops(3)).unwrap()
+}
+
+#[test]
+fn search_picks_a_legal_action() {
+    let mut game = small_game(1);
+    let mut bot = MctsBot::new(1).iterations(50);
+    for _ in 0..20 {
+        if game.is_over() { break; }
+        let action = bot.next_action(&game);
+        assert!(game.legal_actions().contains(&action), "{:?} is not legal", action);
+        game.apply(action).unwrap();
+    }
+}
+
+#[test]
+fn search_is_deterministic_and_leaves_the_game_alone() {
+    let game = small_game(2);
+    let json = game.to_json().unwrap();
+    let first = MctsBot::new(7).iterations(50).search(&game);
+    let second = MctsBot::new(7).iterations(50).search(&game);
+    assert_eq!(first, second);
+    assert_eq!(game.to_json().unwrap(), json);
+}
+
+#[test]
+fn time_limit_stops_the_search() {
+    let game = small_game(3);
+    let mut bot = MctsBot::new(3).iterations(u32::MAX).time_limit_ms(20);
+    assert!(bot.search(&game).is_some());
+}
+
+#[test]
+fn finds_the_winning_attack() {
+    let mut game = small_game(4);
+    deal(&mut game, &[4]);
+    game.set_troops(&1, &10);
+    game.set_troops(&4, &1);
+    game.place_troops(0, game.new_troops()).unwrap();
+    game.attack_phase().unwrap();
+    match MctsBot::new(4).iterations(100).search(&game) {
+        Some(Action::Attack { to: 4, .. }) => (),
+        other => panic!("expected an attack on 4, got {:?}", other),
+    }
+}
+
+#[test]
+fn plays_whole_turns_against_bots() {
+    let config = GameConfig::new().seed(5).starting_troops(3).player_bot(1, Bot::Random);
+    let mut game = Game::with_config(config).unwrap();
+    while !game.is_over() && game.turn_number() < 200 {
+        if game.is_ai_turn() {
+            game.play_ai_turn().unwrap();
+        } else {
+            game.play_mcts_turn(50, 0).unwrap();
+        }
+    }
+    assert_eq!(game.active_players(), vec!(0));
+}
@@ -0,0 +1,56 @@
+extern crate rust_wars;
+
+use rust_wars::{ConfigError, Game, GameConfig, GameError, Map, TerritoryDeal, NEUTRAL_COLOR};
+
+#[test]
+fn neutral_territories_keep_their_troops() {
+    let game = Game::with_config(GameConfig::new().starting_troops(3).neutral_territory(4, 7)).unwrap();
+    assert!(game.is_neutral(4));
+    assert_eq!(game.territory_owner(4), None);
+    assert_eq!(game.get_map().troops(), vec![3, 3, 3, 3, 7]);
+    assert_eq!(game.get_map().color_for(4), NEUTRAL_COLOR);
+    assert!((0..4).all(|t| game.territory_owner(t).is_some()));
+    assert_eq!(game.active_players(), vec![0, 1]);
+    assert!(!game.is_neutral(99));
+
+    // Whichever order the config is built in
+    let later_map = Game::with_config(GameConfig::new().starting_troops(3).neutral_territory(4, 7).map(Map::new())).unwrap();
+    assert_eq!(later_map.neutral_territories(), vec![4]);
+
+    let mut draft = Game::with_config(GameConfig::new().territory_deal(TerritoryDeal::Draft).neutral_territory(0, 2)).unwrap();
+    assert_eq!(draft.unclaimed_territories(), vec![1, 2, 3, 4]);
+    assert_eq!(draft.claim(0), Err(GameError::AlreadyOwned));
+}
+
+#[test]
+fn neutral_territories_can_be_captured() {
+    let mut game = Game::with_config(GameConfig::new().starting_troops(3).neutral_territory(4, 1)).unwrap();
+    for t in [0, 1, 2, 3] { game.assign_territory(t, if t == 3 { 1 } else { 0 }); }
+    game.set_troops(&1, &30);
+    game.place_troops(1, game.new_troops()).unwrap();
+    game.attack_phase().unwrap();
+    assert!(game.attack(1, 4, 20).unwrap());
+    assert_eq!(game.territory_owner(4), Some(0));
+    assert!(!game.is_neutral(4));
+    assert!(game.neutral_territories().is_empty());
+}
+
+#[test]
+fn the_neutral_army_is_dealt_like_a_third_player() {
+    let mut game = Game::with_config(GameConfig::new().starting_troops(3).neutral_army(2)).unwrap();
+    let neutral = game.neutral_territories();
+    assert_eq!(neutral.len(), 1);
+    assert_eq!(game.get_map().troops()[neutral[0] as usize], 2);
+    assert_eq!(game.active_players().len(), 2);
+    assert!(!game.is_over());
+
+    // Once one player is left the game is over, whatever the neutral army still holds
+    for t in (0..5).filter(|t| !neutral.contains(t)) { game.assign_territory(t, 0); }
+    assert!(game.is_over());
+}
+
+#[test]
+fn players_need_territories_after_the_neutral_ones() {
+    let config = GameConfig::new().neutral_territory(0, 1).neutral_territory(1, 1).neutral_territory(2, 1).neutral_territory(3, 1);
+    assert_eq!(Game::with_config(config).err(), Some(ConfigError::NotEnoughTerritories));
+}
@@ -0,0 +1,49 @@
+extern crate rust_wars;
+
+use rust_wars::{battle_odds, Game, GameError};
+
+fn close(a: f64, b: f64) -> bool { (a - b).abs() < 1e-9 }
+
+#[test]
+fn single_rolls_match_the_dice() {
+    // One die each, ties to the defender
+    assert!(close(battle_odds(1, 1).attacker_win(), 15.0 / 36.0));
+    // Three against two, settled in one roll
+    let odds = battle_odds(2, 2);
+    assert_eq!(odds.attacker_survivors().len(), 3);
+    let three_two = battle_odds(3, 1);
+    assert!(three_two.attacker_win() > odds.attacker_win());
+}
+
+#[test]
+fn distributions_add_up() {
+    for (a, d) in [(1, 1), (5, 3), (12, 10), (40, 35)] {
+        let odds = battle_odds(a, d);
+        let attacker: f64 = odds.attacker_survivors().iter().sum();
+        let defender: f64 = odds.defender_survivors().iter().sum();
+        assert!(close(attacker, 1.0) && close(defender, 1.0), "{}v{}", a, d);
+        assert!(close(odds.attacker_win() + odds.defender_win(), 1.0));
+        // Exactly one side is left standing
+        assert!(close(odds.defender_survivors()[0], odds.attacker_win()));
+        assert!(odds.expected_attackers() <= a as f64 && odds.expected_defenders() <= d as f64);
+    }
+}
+
+#[test]
+fn more_troops_means_better_odds() {
+    let odds: Vec<f64> = (1..20).map(|a| battle_odds(a, 10).attacker_win()).collect();
+    assert!(odds.windows(2).all(|w| w[0] < w[1]));
+    // Cached results are the same as fresh ones
+    assert_eq!(battle_odds(30, 30), battle_odds(30, 30));
+}
+
+#[test]
+fn game_odds_leave_one_troop_behind() {
+    let mut game = Game::new();
+    game.set_troops(&0, &4);
+    game.set_troops(&1, &2);
+    assert_eq!(game.attack_odds(0, 1).unwrap(), battle_odds(3, 2));
+    assert!(matches!(game.attack_odds(0, 9), Err(GameError::UnknownTerritory)));
+    assert!(matches!(game.selection_odds(), Err(GameError::NoSelection)));
+    assert!(matches!(game.selection_odds_with(1), Err(GameError::NoSelection)));
+}
@@ -0,0 +1,30 @@
+extern crate rust_wars;
+
+use rust_wars::Game;
+
+#[test]
+fn base_reinforcements_have_a_minimum_of_three() {
+    let mut game = Game::new();
+    game.assign_territory(0, 1);
+    game.assign_territory(2, 1);
+    game.assign_territory(4, 1);
+    game.assign_territory(1, 0);
+    game.assign_territory(3, 0);
+    let reinforcements = game.reinforcements();
+    assert_eq!(reinforcements.base, 3);
+    assert!(reinforcements.countries().is_empty());
+    assert_eq!(game.calc_troop_bonus(), 3);
+}
+
+#[test]
+fn holding_every_territory_in_a_country_awards_its_bonus() {
+    let mut game = Game::new();
+    for t in 0..5 { game.assign_territory(t, 1); }
+    game.assign_territory(0, 0);
+    game.assign_territory(1, 0);
+    let map = game.get_map();
+    let reinforcements = game.reinforcements();
+    assert_eq!(reinforcements.countries(), vec![0]);
+    assert_eq!(reinforcements.bonuses(), vec![map.country_bonus(0)]);
+    assert_eq!(reinforcements.total(), 3 + map.country_bonus(0));
+}
@@ -0,0 +1,79 @@
+extern crate rust_wars;
+
+use rust_wars::{Action, Game, GameConfig, Replay, TurnPhase};
+
+// Places everything on the strongest territory and attacks once from it, for a few turns
+fn play(game: &mut Game, turns: u32) {
+    while game.turn_number() <= turns && !game.is_over() {
+        let player = game.on_player_index() as u32;
+        let troops = game.get_map().troops();
+        let strongest = (0..5).filter(|t| game.territory_owner(*t) == Some(player))
+            .max_by_key(|t| troops[*t]).unwrap();
+        game.apply(Action::PlaceTroops { territory: strongest, troops: game.new_troops() }).unwrap();
+        game.apply(Action::EndPhase).unwrap();
+        let target = (0..5).find(|t| game.territory_owner(*t) != Some(player) && game.get_map().can_attack(strongest, *t));
+        if let Some(to) = target {
+            let dice = game.get_map().troops()[strongest] - 1;
+            game.apply(Action::Attack { from: strongest, to, dice }).unwrap();
+            if game.turn_phase() == TurnPhase::PostAttackFortify {
+                game.apply(Action::Advance { troops: 0 }).unwrap();
+            }
+        }
+        if !game.is_over() {
+            if game.is_place_phase() {
+                let troops = game.new_troops();
+                game.apply(Action::PlaceTroops { territory: strongest, troops }).unwrap();
+            }
+            game.apply(Action::EndTurn).unwrap();
+        }
+    }
+}
+
+fn played_game() -> Game {
+    let mut game = Game::with_config(GameConfig::new().seed(42).starting_troops(3)).unwrap();
+    play(&mut game, 6);
+    game
+}
+
+#[test]
+fn replaying_the_log_reproduces_the_game() {
+    let game = played_game();
+    let mut replay = game.to_replay().unwrap();
+    replay.seek(replay.action_count());
+    assert_eq!(replay.game().to_json().unwrap(), game.to_json().unwrap());
+}
+
+#[test]
+fn replays_survive_a_json_round_trip() {
+    let game = played_game();
+    let json = game.to_replay().unwrap().to_json().unwrap();
+    let mut replay = Replay::from_json(&json).unwrap();
+    replay.seek(usize::MAX);
+    assert_eq!(replay.game().get_map().troops(), game.get_map().troops());
+}
+
+#[test]
+fn replays_step_and_jump_between_turns() {
+    let game = played_game();
+    let mut replay = game.to_replay().unwrap();
+    assert_eq!(replay.turn(), 1);
+    assert!(replay.turn_count() > 2);
+
+    assert!(replay.jump_to_turn(3));
+    assert_eq!(replay.turn(), 3);
+    let turn_start = replay.position();
+    assert_eq!(replay.game().history(), &game.history()[..turn_start]);
+    assert!(replay.game().battles().iter().all(|b| b.action < turn_start));
+    let state = replay.game().to_json().unwrap();
+
+    assert!(replay.step_forward());
+    assert!(replay.step_back());
+    assert_eq!(replay.position(), turn_start);
+    assert_eq!(replay.game().to_json().unwrap(), state);
+
+    assert!(replay.previous_turn());
+    assert_eq!(replay.turn(), 2);
+    assert!(replay.next_turn());
+    assert_eq!(replay.position(), turn_start);
+    assert!(!replay.jump_to_turn(99));
+}
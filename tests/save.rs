@@ -0,0 +1,66 @@
+extern crate rust_wars;
+
+mod common;
+
+use rust_wars::{Game, SaveError, SAVE_VERSION};
+
+use common::deal;
+
+// Written by the first version of the format, before game configs, the history, player names and typed borders
+const FIRST_SAVE: &str = r#"{"version":1,"game":{"map":{"width":16,"height":16,"territories":[{"vertices":[17,23,39,85,66,17],"center":52,"color":1157649,"troops":168,"state":"Dormant","neighbors":[1,2,3]},{"vertices":[23,39,56,107,94,45,23],"center":75,"color":11145489,"troops":289,"state":"Dormant","neighbors":[0,2,4]},{"vertices":[85,39,56,107,169,216,85],"center":104,"color":11145489,"troops":3,"state":"Dormant","neighbors":[0,1,3,4]},{"vertices":[66,85,216,246,128,66],"center":148,"color":11145489,"troops":4,"state":"Dormant","neighbors":[0,2]},{"vertices":[94,220,216,169,107,94],"center":156,"color":1157649,"troops":5,"state":"Dormant","neighbors":[1,2]}],"countries":[{"name":"North","territories":[0,1],"border_color":16711680,"bonus":2},{"name":"South","territories":[2,3],"border_color":65484,"bonus":3}],"background_color":0,"background_index":16777215,"troops_to_place":1,"troop_placement_cache":{}},"players":[{"index":0,"color":11145489,"territories":[2,0,4],"cards":[]},{"index":1,"color":1157649,"territories":[1,3],"cards":[]}],"turn":{"number":1,"player_index":0,"phase":"Place","new_troops":5,"captured_territory":false,"pending_advance":null,"fortifications":0},"deck":{"draw_pile":[{"territory":0,"symbol":"Infantry"},{"territory":1,"symbol":"Cavalry"},{"territory":2,"symbol":"Artillery"},{"territory":3,"symbol":"Infantry"},{"territory":4,"symbol":"Cavalry"},{"territory":null,"symbol":"Wild"},{"territory":null,"symbol":"Wild"}],"discard_pile":[],"trades":0},"card_rules":{"schedule":"Increasing","territory_bonus":2,"forced_trade_at":5,"wild_cards":2},"fortify_rules":{"per_turn":1},"eliminations":[],"rng":{"seed":123,"word_pos_high":0,"word_pos_low":24}}}"#;
+
+// Player 0 has rolled once attacking from 0 into player 1's 2
+fn game_mid_attack() -> Game {
+    let mut game = Game::new();
+    deal(&mut game, &[2, 4]);
+    game.place_troops(0, game.new_troops()).unwrap();
+    game.attack_phase().unwrap();
+    game.attack_single_roll(0, 2).unwrap();
+    game
+}
+
+#[test]
+fn json_round_trip_restores_the_game() {
+    let game = game_mid_attack();
+    let json = game.to_json().unwrap();
+    let restored = Game::from_json(&json).unwrap();
+    assert_eq!(restored.to_json().unwrap(), json);
+}
+
+#[test]
+fn restored_games_roll_the_same_dice() {
+    let mut game = game_mid_attack();
+    let mut from_json = Game::from_json(&game.to_json().unwrap()).unwrap();
+    let mut from_bytes = Game::from_bytes(&game.to_bytes().unwrap()).unwrap();
+
+    let log = game.attack_single_roll(0, 2).unwrap();
+    assert_eq!(from_json.attack_single_roll(0, 2).unwrap(), log);
+    assert_eq!(from_bytes.attack_single_roll(0, 2).unwrap(), log);
+    assert_eq!(from_bytes.to_json().unwrap(), game.to_json().unwrap());
+}
+
+#[test]
+fn other_save_versions_are_rejected() {
+    let version = format!("\"version\":{}", SAVE_VERSION);
+    let json = Game::new().to_json().unwrap().replacen(&version, "\"version\":99", 1);
+    match Game::from_json(&json) {
+        Err(SaveError::UnsupportedVersion(99)) => (),
+        Err(e) => panic!("unexpected error {}", e),
+        Ok(_) => panic!("loaded an unsupported version"),
+    }
+}
+
+#[test]
+fn older_json_saves_still_load() {
+    let mut game = Game::from_json(FIRST_SAVE).unwrap();
+    assert_eq!(game.player_name(1), "Player 2");
+    assert_eq!(game.get_map().neighbors(2), vec![0, 1, 3, 4]);
+    assert_eq!(game.get_map().troops()[0], 168);
+    game.place_troops(0, 1).unwrap();
+    assert!(game.undo().is_ok());
+
+    // Binary saves have to match exactly
+    let mut bytes = Game::new().to_bytes().unwrap();
+    bytes[0] = 9;
+    assert!(matches!(Game::from_bytes(&bytes), Err(SaveError::UnsupportedVersion(9))));
+}
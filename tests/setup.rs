@@ -0,0 +1,76 @@
+extern crate rust_wars;
+
+use rust_wars::{Agent, ConfigError, Game, GameConfig, GameError, TerritoryDeal, TurnPhase};
+use rust_wars::ai::GreedyBot;
+
+fn draft(army: u32) -> Game {
+    Game::with_config(GameConfig::new().territory_deal(TerritoryDeal::Draft).starting_army(army)).unwrap()
+}
+
+#[test]
+fn players_take_turns_claiming() {
+    let mut game = draft(5);
+    assert_eq!(game.turn_phase(), TurnPhase::Claim);
+    assert!(!game.is_over());
+    assert_eq!(game.unclaimed_territories().len(), 5);
+
+    game.claim(2).unwrap();
+    assert_eq!(game.territory_owner(2), Some(0));
+    assert_eq!(game.get_map().troops()[2], 1);
+    assert_eq!(game.troops_to_deploy(0), 4);
+    assert_eq!(game.on_player_index(), 1);
+    assert_eq!(game.claim(2), Err(GameError::AlreadyOwned));
+    assert_eq!(game.place_troops(0, 1), Err(GameError::WrongPhase));
+    assert_eq!(game.end_turn(), Err(GameError::WrongPhase));
+
+    for t in [0, 1, 3, 4] { game.map_click_action(t).unwrap(); }
+    assert!(game.unclaimed_territories().is_empty());
+    // Player 0 claimed last, so player 1 deploys first
+    assert_eq!(game.turn_phase(), TurnPhase::Deploy);
+    assert_eq!(game.on_player_index(), 1);
+}
+
+#[test]
+fn starting_armies_are_deployed_in_turns() {
+    let config = GameConfig::new().starting_army(6).troops_per_deploy(2);
+    let mut game = Game::with_config(config).unwrap();
+    assert_eq!(game.turn_phase(), TurnPhase::Deploy);
+    assert!(game.get_map().troops().iter().all(|t| *t == 1));
+
+    let owned = |game: &Game, player: u32| (0..5).find(|t| game.territory_owner(*t) == Some(player)).unwrap();
+    assert_eq!(game.deploy_size(), 2);
+    assert_eq!(game.deploy(owned(&game, 0), 3), Err(GameError::NotEnoughTroops));
+    assert_eq!(game.deploy(owned(&game, 1), 2), Err(GameError::NotOwned));
+    while game.is_setup_phase() {
+        let player = game.on_player_index() as u32;
+        game.deploy(owned(&game, player), game.deploy_size()).unwrap();
+    }
+    assert_eq!(game.get_map().troops().iter().sum::<u32>(), 12);
+    assert_eq!(game.turn_phase(), TurnPhase::Place);
+    assert_eq!(game.on_player_index(), 0);
+    assert_eq!(game.turn_number(), 1);
+    assert!(game.new_troops() > 0);
+}
+
+#[test]
+fn the_army_has_to_cover_the_territories() {
+    assert_eq!(Game::with_config(GameConfig::new().starting_army(2)).err(), Some(ConfigError::ArmyTooSmall));
+    assert!(Game::with_config(GameConfig::new().starting_army(3)).is_ok());
+}
+
+#[test]
+fn bots_play_through_the_setup() {
+    let mut game = draft(8);
+    let mut bot = GreedyBot;
+    while game.is_setup_phase() {
+        let action = bot.next_action(&game);
+        game.apply(action).unwrap();
+    }
+    assert_eq!(game.turn_phase(), TurnPhase::Place);
+    assert_eq!(game.get_map().troops().iter().sum::<u32>(), 16);
+    // Starting the first turn hands play over, so the deploys can no longer be taken back
+    assert!(!game.can_undo());
+
+    let loaded = Game::from_json(&game.to_json().unwrap()).unwrap();
+    assert_eq!(loaded.to_json().unwrap(), game.to_json().unwrap());
+}
@@ -0,0 +1,86 @@
+extern crate rust_wars;
+
+use rust_wars::{BorderKind, Game, GameConfig, Map, MapError};
+
+const BOARD: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- exported board -->
+<WarGearXML>
+  <board boardname="Islands &amp; Seas" width="200" height="100" boardtheme="sea">
+    <territories>
+      <territory tid="11" name="Harbor" xpos="20" ypos="30"/>
+      <territory tid="12" name="Reef" xpos="80" ypos="30" maxunits="10"/>
+      <territory tid="13" name="Cove" xpos="140" ypos="70"/>
+      <territory tid="14" name="Fort" xpos="190" ypos="90"/>
+    </territories>
+    <borders>
+      <border fromid="11" toid="12" direction="Two-way"/>
+      <border fromid="12" toid="13" direction="One-way"/>
+      <border fromid="13" toid="14"/>
+      <border fromid="11" toid="14" direction="One-way" type="Artillery"/>
+      <border fromid="12" toid="14" type="View Only"/>
+    </borders>
+    <continents>
+      <continent cid="1" name="West" bonus="2" members="11, 12"/>
+      <continent cid="2" name="East" bonus="3">
+        <member tid="13"/>
+        <member tid="14"/>
+      </continent>
+    </continents>
+    <gameplay fog="true"/>
+  </board>
+</WarGearXML>"#;
+
+#[test]
+fn boards_are_imported() {
+    let import = Map::from_wargear(BOARD).unwrap();
+    let map = import.map();
+    assert_eq!(map.name(), "Islands & Seas");
+    assert_eq!(map.territory_count(), 4);
+    assert_eq!(map.territory_name(2), "Cove");
+    assert_eq!(map.neighbors(0), vec![1, 3]);
+    assert_eq!(map.neighbors(1), vec![0, 2]);
+    // One way, so the cove can't reach back to the reef
+    assert!(map.can_attack(1, 2) && !map.can_attack(2, 1));
+    assert_eq!(map.border_kind(2, 1), Some(BorderKind::DefendOnly));
+    // Artillery
+    assert!(map.can_attack(0, 3) && !map.can_move(0, 3) && !map.can_attack(3, 0));
+    assert!(map.validate().is_empty());
+    assert_eq!(map.country_territories(1), vec![2, 3]);
+    assert_eq!(map.country_bonus(0), 2);
+    assert!(map.width() >= 20 && map.height() >= 10);
+    assert!(Game::with_config(GameConfig::new().map(map)).is_ok());
+}
+
+#[test]
+fn unsupported_features_are_reported() {
+    let unsupported = Map::from_wargear(BOARD).unwrap().unsupported();
+    for feature in ["board setting `boardtheme`", "territory setting `maxunits`", "`gameplay` elements", "View Only borders"] {
+        assert!(unsupported.iter().any(|u| u.starts_with(feature)), "{} missing from {:?}", feature, unsupported);
+    }
+}
+
+#[test]
+fn broken_exports_are_rejected() {
+    assert!(matches!(Map::from_wargear("<board><territories>"), Err(MapError::Xml(_))));
+    assert!(matches!(Map::from_wargear("<board></board>"), Err(MapError::NoTerritories)));
+    let unknown = BOARD.replace("toid=\"14\"/>", "toid=\"99\"/>");
+    assert!(matches!(Map::from_wargear(&unknown), Err(MapError::Xml(_))));
+    let twice = BOARD.replace("tid=\"13\" name=\"Cove\"", "tid=\"12\" name=\"Cove\"");
+    assert!(matches!(Map::from_wargear(&twice), Err(MapError::Xml(e)) if e.contains("12")));
+    let far_off = BOARD.replace("xpos=\"190\"", "xpos=\"1e10\"");
+    assert!(matches!(Map::from_wargear(&far_off), Err(MapError::Xml(_))));
+    let huge = BOARD.replace("xpos=\"190\" ypos=\"90\"", "xpos=\"4000000000\" ypos=\"4000000000\"");
+    assert!(matches!(Map::from_wargear(&huge), Err(MapError::TooLarge { .. })));
+}
+
+#[test]
+fn opposite_one_way_borders_make_a_two_way_border() {
+    let board = BOARD.replace(
+        r#"<border fromid="13" toid="14"/>"#,
+        r#"<border fromid="13" toid="14" direction="One-way"/><border fromid="14" toid="13" direction="One-way"/>"#,
+    );
+    let map = Map::from_wargear(&board).unwrap().map();
+    assert!(map.can_attack(2, 3) && map.can_attack(3, 2));
+    assert_eq!(map.border_kind(2, 3), Some(BorderKind::Normal));
+    assert_eq!(map.border_kind(3, 2), Some(BorderKind::Normal));
+}
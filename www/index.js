@@ -1,11 +1,28 @@
-import { Game, TurnPhase } from "rust-wars";
+import { Game, TurnPhase, describe_error } from "rust-wars";
 
 const MAP_SCALE = 20;
 
 const BORDER_COLOR = "#DDDDDD";
 const GRID_COLOR = "#CCCCCC";
 
-const game = Game.new();
+const SAVE_KEY = "rust-wars-save";
+
+// Resume the last game from localStorage, falling back to a new one
+const loadGame = () => {
+    const saved = localStorage.getItem(SAVE_KEY);
+    if (saved) {
+        try {
+            return Game.from_json(saved);
+        } catch (error) {
+            console.warn("Discarding saved game: " + error);
+            localStorage.removeItem(SAVE_KEY);
+        }
+    }
+    return Game.new();
+};
+const saveGame = () => localStorage.setItem(SAVE_KEY, game.to_json());
+
+const game = loadGame();
 const map = game.get_map();
 const width = map.width();
 const height = map.height();
@@ -13,6 +30,17 @@ const territoryCount = map.territory_count();
 const nodes = map.centers();
 
 
+// Illegal moves throw a GameError; log why and carry on
+const tryAction = (action) => {
+    try {
+        action();
+        return true;
+    } catch (error) {
+        console.warn(describe_error(error));
+        return false;
+    }
+};
+
 const getEventCoordinates = (canvas, event) => {
     const rect = canvas.getBoundingClientRect();
     const x = event.clientX - rect.left;
@@ -45,7 +73,7 @@ troopCanvas.height = (height - 1) * MAP_SCALE;
 troopCanvas.addEventListener('mousedown', function (e) {
     let coordinates = getEventCoordinates(troopCanvas, e);
     let territory = territoryFromCoordinates(coordinates);
-    let result = game.map_click_action(territory);
+    let result = tryAction(() => game.map_click_action(territory));
     if (result) renderLoop();
 });
 
@@ -88,9 +116,9 @@ clearPlacementButton.addEventListener('click', event => {
 
 let applyPlacementButton = document.getElementById("apply-placement")
 applyPlacementButton.addEventListener('click', e => {
-    game.commit_placement_cache();
+    tryAction(() => game.commit_placement_cache());
     if (game.troops_available_for_placement() === 0) {
-        game.attack_phase();
+        tryAction(() => game.attack_phase());
         hidePlacementElements(true);
         placeButton.disabled = true;
     }
@@ -99,7 +127,7 @@ applyPlacementButton.addEventListener('click', e => {
 
 let placeButton = document.getElementById('placement-button')
 placeButton.addEventListener('click', e => {
-    game.place_phase();
+    if (!tryAction(() => game.place_phase())) return;
     initializePlacementSelector();
     hidePlacementElements(false);
 });
@@ -130,24 +158,37 @@ placeTroopSelector.onchange = () => {
 
 let attackButton = document.getElementById("attack-button")
 attackButton.addEventListener('click', e => {
-    game.attack_phase();
-    hidePlacementElements(true);
+    if (tryAction(() => game.attack_phase())) hidePlacementElements(true);
 })
 
 let fortifyButton = document.getElementById("fortify-button")
 fortifyButton.addEventListener('click', e => {
-    game.fortify_phase();
-    hidePlacementElements(true);
+    if (tryAction(() => game.fortify_phase())) hidePlacementElements(true);
 })
 
 let endTurnButton = document.getElementById('end-turn-button')
 endTurnButton.addEventListener('click', e => {
-    game.init_turn();
+    tryAction(() => game.end_turn());
+    renderLoop();
+})
+let undoButton = document.getElementById('undo-button')
+undoButton.addEventListener('click', e => {
+    tryAction(() => game.undo());
+    renderLoop();
+})
+
+let redoButton = document.getElementById('redo-button')
+redoButton.addEventListener('click', e => {
+    tryAction(() => game.redo());
     renderLoop();
 })
 const troopCounterDisplay = document.getElementById('troop-placement-counter')
 const updateTroopPlacementCounter = () => {
-    if (game.is_place_phase()) {
+    if (game.is_setup_phase()) {
+        // Clicking a territory claims it or deploys to it straight away
+        troopCounterDisplay.hidden = false;
+        troopCounterDisplay.innerText = game.troops_to_deploy(game.on_player_index()).toString() + " to deploy";
+    } else if (game.is_place_phase()) {
         troopCounterDisplay.hidden = false;
         let denominator = game.new_troops(); // TODO
         let numerator = game.troops_staged_for_placement();
@@ -323,18 +364,18 @@ const drawArrow = (fromx, fromy, tox, toy) => {
 const attackModal = document.getElementById("attack-modal");
 const attackTroopSelector = document.getElementById("troop-attack-selector");
 attackTroopSelector.onchange = () => {
-    game.attack_with(attackTroopSelector.selectedIndex + 1);
+    tryAction(() => game.attack_with(attackTroopSelector.selectedIndex + 1));
     attackModal.style.zIndex = -1;
     renderLoop();
 }
 const attackAndTailButton = document.getElementById('attack-modal-button-tail');
 attackAndTailButton.addEventListener('click', e => {
-    game.attack_tail();
+    tryAction(() => game.attack_tail());
     renderLoop();
 });
 const attackAllButton = document.getElementById('attack-modal-button-all');
 attackAllButton.addEventListener('click', e => {
-    game.attack_all();
+    tryAction(() => game.attack_all());
     renderLoop();
 });
 
@@ -354,7 +395,8 @@ const showAttackPrompt = () => {
     // attackTroopSelector.options.length = troops;
     for (const i of Array(troops).keys()) {
         if (i === 0) continue;
-        attackTroopSelector.options[attackTroopSelector.options.length] = new Option(i.toString(),i.toString());
+        let win = Math.round(game.selection_odds_with(i).attacker_win() * 100);
+        attackTroopSelector.options[attackTroopSelector.options.length] = new Option(`${i} (${win}%)`, i.toString());
     }
     attackModal.style.zIndex = 9999;
 }
@@ -366,13 +408,13 @@ const hideAttackPrompt = () => {
 const fortifyModal = document.getElementById("fortify-modal");
 const fortifyTroopSelector = document.getElementById("troop-fortify-selector");
 fortifyTroopSelector.onchange = () => {
-    game.fortify_troops(fortifyTroopSelector.selectedIndex + 1);
+    tryAction(() => game.fortify_troops(fortifyTroopSelector.selectedIndex + 1));
     fortifyModal.style.zIndex = -1;
     renderLoop();
 }
 const fortifyAllButton = document.getElementById('fortify-modal-button-all');
 fortifyAllButton.addEventListener('click', e => {
-    game.fortify_all();
+    tryAction(() => game.fortify_all());
     fortifyModal.style.zIndex = -1;
     renderLoop();
 })
@@ -410,6 +452,8 @@ const updateTroops = () => {
     }
 };
 const updateControls = () => {
+    undoButton.disabled = !game.can_undo();
+    redoButton.disabled = !game.can_redo();
     initializePlacementSelector();
     updateTroopPlacementCounter();
     if (game.is_place_phase()) {
@@ -426,13 +470,34 @@ const updateControls = () => {
         hideFortifyPrompt();
     }
 }
+// Computer players move straight away once it is their turn
+const playComputerTurns = () => {
+    while (game.is_ai_turn()) {
+        if (!tryAction(() => game.play_ai_turn())) break;
+    }
+};
+
+// Dice from the most recent attack, one roll per line
+const diceLog = document.getElementById("dice-log");
+const updateDiceLog = () => {
+    const battle = game.last_battle();
+    if (!battle) return;
+    diceLog.innerText = battle.rolls().map(roll =>
+        `${roll.attack_faces().join(" ")} vs ${roll.defense_faces().join(" ")}` +
+        ` (-${roll.attacker_losses} / -${roll.defender_losses})`
+    ).join("\n");
+};
+
 const renderLoop = () => {
+    playComputerTurns();
     // mapContext.globalCompositeOperation = 'destination-over';
     drawMap();
     drawTroopContainers();
     drawMovementArrow();
     updateTroops();
     updateControls();
+    updateDiceLog();
+    saveGame();
     gameStatus(); // todo: restart/disable game after someone wins
 };
 